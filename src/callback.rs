@@ -0,0 +1,196 @@
+use crate::{PullTimer, TimeUnit, TimerId};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+type Once<Ctx, D> = Box<dyn FnOnce(&mut Ctx, &mut CallbackTimer<Ctx, D>)>;
+type Repeating<Ctx, D> = Box<dyn FnMut(&mut Ctx, &mut CallbackTimer<Ctx, D>)>;
+
+enum Callback<Ctx, D> {
+    Once(Once<Ctx, D>),
+    Repeating(Repeating<Ctx, D>),
+}
+
+/// A timer that runs closures when they are due instead of handing back
+/// events.
+///
+/// Callbacks get a context of type `Ctx` along with the timer itself, so
+/// they can add or cancel other timers while running.
+pub struct CallbackTimer<Ctx, D = u32> {
+    timer: PullTimer<(), D>,
+    callbacks: BTreeMap<TimerId, Callback<Ctx, D>>,
+}
+
+impl<Ctx> CallbackTimer<Ctx> {
+    pub fn new() -> CallbackTimer<Ctx> {
+        CallbackTimer::default()
+    }
+}
+
+impl<Ctx, D: TimeUnit> CallbackTimer<Ctx, D> {
+    pub fn next_in(&self) -> Option<D> {
+        self.timer.next_in()
+    }
+
+    /// Updates the timer, then runs every due callback in firing order.
+    /// Returns the number of callbacks run.
+    ///
+    /// Callbacks added with a deadline of zero while running are run by the
+    /// same call.
+    pub fn update_and_run(&mut self, elapsed: D, context: &mut Ctx) -> usize {
+        self.timer.update(elapsed);
+
+        let mut run = 0;
+        while let Some(expired) = self.timer.poll_expired() {
+            let id = expired.id;
+
+            match self.callbacks.remove(&id) {
+                Some(Callback::Once(callback)) => callback(context, self),
+                Some(Callback::Repeating(mut callback)) => {
+                    callback(context, self);
+                    // The callback may have cancelled itself.
+                    if self.timer.contains(id) {
+                        self.callbacks.insert(id, Callback::Repeating(callback));
+                    }
+                }
+                None => continue,
+            }
+            run += 1;
+        }
+        run
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `deadline` is beyond the timer's horizon.
+    pub fn add<F>(&mut self, deadline: D, callback: F) -> TimerId
+    where
+        F: FnOnce(&mut Ctx, &mut CallbackTimer<Ctx, D>) + 'static,
+    {
+        let id = self.timer.add(deadline, ());
+        self.callbacks
+            .insert(id, Callback::Once(Box::new(callback)));
+        id
+    }
+
+    /// Adds a callback that runs every `period` until cancelled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero or beyond the timer's horizon.
+    pub fn add_interval<F>(&mut self, period: D, callback: F) -> TimerId
+    where
+        F: FnMut(&mut Ctx, &mut CallbackTimer<Ctx, D>) + 'static,
+    {
+        let id = self.timer.add_interval(period, ());
+        self.callbacks
+            .insert(id, Callback::Repeating(Box::new(callback)));
+        id
+    }
+
+    /// Cancels a pending callback, returning whether it was pending.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        self.callbacks.remove(&id);
+        self.timer.cancel(id).is_some()
+    }
+
+    pub fn contains(&self, id: TimerId) -> bool {
+        self.timer.contains(id)
+    }
+
+    pub fn remaining(&self, id: TimerId) -> Option<D> {
+        self.timer.remaining(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.timer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timer.is_empty()
+    }
+}
+
+impl<Ctx, D: TimeUnit> Default for CallbackTimer<Ctx, D> {
+    fn default() -> Self {
+        CallbackTimer {
+            timer: PullTimer::default(),
+            callbacks: BTreeMap::new(),
+        }
+    }
+}
+
+impl<Ctx, D: fmt::Debug> fmt::Debug for CallbackTimer<Ctx, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackTimer")
+            .field("timer", &self.timer)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn callback_timer_runs_due_callbacks_in_order() {
+        let mut timer = CallbackTimer::new();
+        let mut log = Vec::new();
+
+        timer.add(20, |log: &mut Vec<_>, _| log.push("second"));
+        timer.add(10, |log: &mut Vec<_>, _| log.push("first"));
+
+        assert_eq!(timer.update_and_run(15, &mut log), 1);
+        assert_eq!(timer.update_and_run(15, &mut log), 1);
+        assert_eq!(log, ["first", "second"]);
+        assert!(timer.is_empty());
+    }
+
+    #[test]
+    fn callback_timer_callbacks_schedule_and_cancel() {
+        let mut timer = CallbackTimer::new();
+        let mut log = Vec::new();
+
+        let doomed = timer.add(30, |log: &mut Vec<_>, _| log.push("doomed"));
+        timer.add(10, move |log: &mut Vec<_>, timer: &mut CallbackTimer<_>| {
+            log.push("first");
+            timer.cancel(doomed);
+            timer.add(0, |log: &mut Vec<_>, _| log.push("follow-up"));
+            timer.add(5, |log: &mut Vec<_>, _| log.push("later"));
+        });
+
+        assert_eq!(timer.update_and_run(10, &mut log), 2);
+        assert_eq!(log, ["first", "follow-up"]);
+
+        assert_eq!(timer.update_and_run(100, &mut log), 1);
+        assert_eq!(log, ["first", "follow-up", "later"]);
+        assert!(timer.is_empty());
+    }
+
+    #[test]
+    fn callback_timer_repeats_until_cancelled() {
+        let mut timer = CallbackTimer::new();
+        let mut state = (0, None);
+
+        let id = timer.add_interval(10, |(ticks, own): &mut (u32, Option<TimerId>), timer| {
+            *ticks += 1;
+            if *ticks == 3 {
+                timer.cancel(own.unwrap());
+            }
+        });
+        state.1 = Some(id);
+
+        for _ in 0..5 {
+            timer.update_and_run(10, &mut state);
+        }
+        assert_eq!(state.0, 3);
+        assert!(!timer.contains(id));
+
+        let id = timer.add_interval(10, |(ticks, _): &mut (u32, _), _| *ticks += 1);
+        assert_eq!(timer.update_and_run(25, &mut state), 2);
+        assert!(timer.cancel(id));
+        assert_eq!(timer.update_and_run(100, &mut state), 0);
+        assert_eq!(state.0, 5);
+    }
+}
@@ -0,0 +1,281 @@
+use crate::{PullTimer, TimerId};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// A monotonic source of time.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// The system's monotonic clock, through [`Instant::now`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdClock;
+
+impl Clock for StdClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to, for deterministic tests.
+///
+/// Clones share the same time, so a test can keep one and hand the other
+/// to the code under test.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    start: Instant,
+    elapsed: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock {
+            start: Instant::now(),
+            elapsed: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Returns how far the clock has been advanced since it was created.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed.load(Ordering::SeqCst))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the clock would be advanced by more than `u64::MAX`
+    /// nanoseconds in total.
+    pub fn advance(&self, by: Duration) {
+        let by = u64::try_from(by.as_nanos()).expect("manual clock overflowed");
+        self.elapsed
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |elapsed| {
+                elapsed.checked_add(by)
+            })
+            .expect("manual clock overflowed");
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+}
+
+/// A [`PullTimer`] that reads elapsed time from a [`Clock`] itself.
+///
+/// Time is counted in ticks of a fixed resolution, one millisecond unless
+/// chosen otherwise. Time that does not add up to a whole tick is carried
+/// over to the next update instead of being dropped, and deadlines are
+/// rounded up so events never fire early.
+#[derive(Debug, Clone)]
+pub struct ClockedTimer<T, C = StdClock> {
+    timer: PullTimer<T, u64>,
+    clock: C,
+    resolution: Duration,
+    last: Instant,
+    carry: Duration,
+}
+
+impl<T> ClockedTimer<T> {
+    pub fn new() -> ClockedTimer<T> {
+        ClockedTimer::with_clock(StdClock)
+    }
+}
+
+impl<T, C: Clock> ClockedTimer<T, C> {
+    pub fn with_clock(clock: C) -> ClockedTimer<T, C> {
+        ClockedTimer::with_resolution(clock, Duration::from_millis(1))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `resolution` is zero.
+    pub fn with_resolution(clock: C, resolution: Duration) -> ClockedTimer<T, C> {
+        assert!(!resolution.is_zero(), "resolution must not be zero");
+
+        ClockedTimer {
+            timer: PullTimer::default(),
+            last: clock.now(),
+            clock,
+            resolution,
+            carry: Duration::ZERO,
+        }
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    pub fn resolution(&self) -> Duration {
+        self.resolution
+    }
+
+    pub fn next_in(&self) -> Option<Duration> {
+        self.timer.next_in().map(|ticks| self.until(ticks))
+    }
+
+    pub fn add(&mut self, after: Duration, event: T) -> TimerId {
+        self.sync();
+        self.schedule(after, event)
+    }
+
+    pub fn add_at(&mut self, deadline: Instant, event: T) -> TimerId {
+        self.sync();
+        self.schedule(deadline.saturating_duration_since(self.last), event)
+    }
+
+    pub fn cancel(&mut self, id: TimerId) -> Option<T> {
+        self.timer.cancel(id)
+    }
+
+    pub fn contains(&self, id: TimerId) -> bool {
+        self.timer.contains(id)
+    }
+
+    pub fn remaining(&self, id: TimerId) -> Option<Duration> {
+        self.timer.remaining(id).map(|ticks| self.until(ticks))
+    }
+
+    pub fn len(&self) -> usize {
+        self.timer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timer.is_empty()
+    }
+
+    pub fn poll(&mut self) -> Option<T> {
+        self.sync();
+        self.timer.poll()
+    }
+
+    /// Moves the time elapsed since the last sync into the timer, keeping
+    /// any partial tick in `carry`.
+    fn sync(&mut self) {
+        let now = self.clock.now();
+        let elapsed = now
+            .saturating_duration_since(self.last)
+            .saturating_add(self.carry);
+        let resolution = self.resolution.as_nanos();
+
+        let ticks = elapsed.as_nanos() / resolution;
+        self.carry = duration_from_nanos(elapsed.as_nanos() % resolution);
+        self.last = now;
+        self.timer.update(u64::try_from(ticks).unwrap_or(u64::MAX));
+    }
+
+    /// Adds an event `after` from the last sync, rounding up to whole ticks.
+    fn schedule(&mut self, after: Duration, event: T) -> TimerId {
+        let ticks = after
+            .saturating_add(self.carry)
+            .as_nanos()
+            .div_ceil(self.resolution.as_nanos());
+        self.timer
+            .add(u64::try_from(ticks).unwrap_or(u64::MAX), event)
+    }
+
+    /// Returns the real time left until `ticks` more ticks have passed.
+    fn until(&self, ticks: u64) -> Duration {
+        let pending = self
+            .clock
+            .now()
+            .saturating_duration_since(self.last)
+            .saturating_add(self.carry);
+        let total = duration_from_nanos(self.resolution.as_nanos() * u128::from(ticks));
+        total.saturating_sub(pending)
+    }
+}
+
+impl<T> Default for ClockedTimer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn duration_from_nanos(nanos: u128) -> Duration {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+    match u64::try_from(nanos / NANOS_PER_SEC) {
+        Ok(secs) => Duration::new(secs, (nanos % NANOS_PER_SEC) as u32),
+        Err(_) => Duration::MAX,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_is_shared_between_clones() {
+        let clock = ManualClock::new();
+        let other = clock.clone();
+        let start = other.now();
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(other.now() - start, Duration::from_secs(2));
+        assert_eq!(other.elapsed(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn clocked_timer_fires_on_time() {
+        let clock = ManualClock::new();
+        let mut timer = ClockedTimer::with_clock(clock.clone());
+
+        timer.add(Duration::from_millis(10), "ten");
+        timer.add_at(clock.now() + Duration::from_millis(25), "twenty-five");
+        assert_eq!(timer.next_in(), Some(Duration::from_millis(10)));
+
+        clock.advance(Duration::from_millis(9));
+        assert_eq!(timer.poll(), None);
+        assert_eq!(timer.next_in(), Some(Duration::from_millis(1)));
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(timer.poll(), Some("ten"));
+
+        clock.advance(Duration::from_millis(20));
+        assert_eq!(timer.next_in(), Some(Duration::ZERO));
+        assert_eq!(timer.poll(), Some("twenty-five"));
+        assert!(timer.is_empty());
+    }
+
+    #[test]
+    fn clocked_timer_carries_partial_ticks() {
+        let clock = ManualClock::new();
+        let mut timer = ClockedTimer::with_clock(clock.clone());
+
+        timer.add(Duration::from_millis(3), ());
+
+        for _ in 0..5 {
+            clock.advance(Duration::from_micros(500));
+            assert_eq!(timer.poll(), None);
+        }
+
+        clock.advance(Duration::from_micros(500));
+        assert_eq!(timer.poll(), Some(()));
+    }
+
+    #[test]
+    fn clocked_timer_never_fires_early() {
+        let clock = ManualClock::new();
+        let mut timer = ClockedTimer::with_clock(clock.clone());
+
+        clock.advance(Duration::from_micros(700));
+        timer.add(Duration::from_micros(1500), ());
+
+        clock.advance(Duration::from_micros(1499));
+        assert_eq!(timer.poll(), None);
+        assert_eq!(timer.next_in(), Some(Duration::from_micros(801)));
+
+        clock.advance(Duration::from_micros(800));
+        assert_eq!(timer.poll(), None);
+
+        clock.advance(Duration::from_micros(1));
+        assert_eq!(timer.poll(), Some(()));
+    }
+}
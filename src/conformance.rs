@@ -0,0 +1,230 @@
+//! A conformance suite for [`Timer`] implementations.
+//!
+//! [`run`] drives a fresh timer through each scenario and compares the
+//! result with [`PullTimer`], panicking on the first difference. Call it
+//! from a test:
+//!
+//! ```
+//! pull_timer::conformance::run(pull_timer::TreeTimer::new);
+//! ```
+
+use crate::{PullTimer, Timer};
+use alloc::vec::Vec;
+
+/// Runs every scenario against timers created by `new`.
+///
+/// # Panics
+///
+/// Panics if a timer's ordering or timing differs from [`PullTimer`].
+pub fn run<X, F>(mut new: F)
+where
+    X: Timer<u32, Time = u32>,
+    F: FnMut() -> X,
+{
+    preserves_fifo_order(new());
+    fires_in_order(new());
+    fires_in_time(new());
+    update_spans_several_deadlines(new());
+    reports_next_in(new());
+    removes_events(new());
+    fires_after_remove(new());
+    counts_pending_events(new());
+    handles_large_deadlines(new());
+    matches_pull_timer(new());
+}
+
+fn drain(timer: &mut impl Timer<u32, Time = u32>) -> Vec<u32> {
+    core::iter::from_fn(|| timer.poll()).collect()
+}
+
+fn preserves_fifo_order(mut timer: impl Timer<u32, Time = u32>) {
+    timer.add(0, 1);
+    timer.add(0, 2);
+    timer.add(5, 3);
+    timer.update(10);
+    timer.add(0, 4);
+
+    assert_eq!(
+        drain(&mut timer),
+        [1, 2, 3, 4],
+        "equal deadlines must fire in insertion order"
+    );
+}
+
+fn fires_in_order(mut timer: impl Timer<u32, Time = u32>) {
+    timer.add(4, 4);
+    timer.add(3, 3);
+    timer.add(2, 2);
+    timer.add(1, 1);
+
+    timer.update(4);
+
+    assert_eq!(
+        drain(&mut timer),
+        [1, 2, 3, 4],
+        "events must fire in deadline order"
+    );
+}
+
+fn fires_in_time(mut timer: impl Timer<u32, Time = u32>) {
+    timer.add(40, 40);
+    timer.add(20, 20);
+    timer.add(0, 0);
+    timer.add(30, 30);
+    timer.add(10, 10);
+
+    for now in 0..=41 {
+        let fired = drain(&mut timer);
+        if now % 10 == 0 && now <= 40 {
+            assert_eq!(fired, [now], "event must fire exactly at its deadline");
+        } else {
+            assert_eq!(fired, [], "no event is due at {now}");
+        }
+        timer.update(1);
+    }
+}
+
+fn update_spans_several_deadlines(mut timer: impl Timer<u32, Time = u32>) {
+    timer.add(3, 3);
+    timer.add(5, 5);
+
+    timer.update(4);
+
+    assert_eq!(timer.poll(), Some(3));
+    assert_eq!(timer.poll(), None);
+    assert_eq!(
+        timer.next_in(),
+        Some(1),
+        "elapsed time must only be counted once"
+    );
+}
+
+fn reports_next_in(mut timer: impl Timer<u32, Time = u32>) {
+    assert_eq!(timer.next_in(), None);
+
+    timer.add(0, 0);
+    timer.add(20, 20);
+    timer.add(10, 10);
+
+    assert_eq!(timer.next_in(), Some(0));
+    assert_eq!(timer.poll(), Some(0));
+    assert_eq!(timer.next_in(), Some(10));
+
+    timer.update(10);
+    assert_eq!(timer.next_in(), Some(0));
+    assert_eq!(timer.poll(), Some(10));
+
+    timer.update(3);
+    assert_eq!(timer.next_in(), Some(7));
+
+    timer.update(100);
+    assert_eq!(timer.next_in(), Some(0), "overdue events are due now");
+}
+
+fn removes_events(mut timer: impl Timer<u32, Time = u32>) {
+    timer.add(100, 1);
+    timer.add(100, 1);
+    timer.update(50);
+
+    assert_eq!(timer.remove(1), Some(50));
+    assert_eq!(timer.remove(2), None);
+    assert_eq!(timer.remove(1), Some(50));
+    assert_eq!(timer.remove(1), None);
+    assert_eq!(timer.next_in(), None);
+}
+
+fn fires_after_remove(mut timer: impl Timer<u32, Time = u32>) {
+    timer.add(30, 30);
+    timer.add(20, 20);
+    timer.add(40, 40);
+    timer.add(10, 10);
+    timer.add(50, 50);
+
+    assert_eq!(timer.remove(50), Some(50));
+    assert_eq!(timer.remove(10), Some(10));
+
+    for now in 0..=41 {
+        if let Some(event) = timer.poll() {
+            assert_eq!(event, now, "removing an event must not shift the others");
+        }
+        timer.update(1);
+    }
+    assert!(timer.is_empty());
+}
+
+fn counts_pending_events(mut timer: impl Timer<u32, Time = u32>) {
+    assert!(timer.is_empty());
+
+    timer.add(5, 1);
+    timer.add(0, 2);
+    timer.add(9, 3);
+    assert_eq!(timer.len(), 3);
+
+    timer.poll();
+    timer.remove(3);
+    assert_eq!(timer.len(), 1);
+    assert!(!timer.is_empty());
+
+    timer.update(5);
+    timer.poll();
+    assert!(timer.is_empty());
+}
+
+fn handles_large_deadlines(mut timer: impl Timer<u32, Time = u32>) {
+    timer.add(u32::MAX, 3);
+    timer.add(u32::MAX - 1, 2);
+    timer.add(1 << 31, 1);
+    timer.add(0, 0);
+    assert_eq!(timer.poll(), Some(0));
+
+    timer.update((1 << 31) - 1);
+    assert_eq!(timer.poll(), None);
+    assert_eq!(timer.next_in(), Some(1));
+
+    timer.update(u32::MAX);
+    assert_eq!(
+        drain(&mut timer),
+        [1, 2, 3],
+        "deadlines near the end of the range must fire in order"
+    );
+
+    timer.add(u32::MAX, 4);
+    timer.update(u32::MAX - 1);
+    assert_eq!(timer.next_in(), Some(1));
+    timer.update(1);
+    assert_eq!(timer.poll(), Some(4));
+}
+
+fn matches_pull_timer(mut timer: impl Timer<u32, Time = u32>) {
+    let mut reference = PullTimer::new();
+    let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+    let mut random = |bound: u32| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % u64::from(bound)) as u32
+    };
+
+    for event in 0..2000 {
+        match random(4) {
+            0 | 1 => {
+                let deadline = random(300);
+                timer.add(deadline, event);
+                reference.add(deadline, event);
+            }
+            2 => {
+                let elapsed = random(40);
+                timer.update(elapsed);
+                reference.update(elapsed);
+            }
+            _ => {
+                let target = random(event + 1);
+                assert_eq!(timer.remove(target), reference.remove(target));
+            }
+        }
+
+        assert_eq!(timer.next_in(), reference.next_in());
+        assert_eq!(timer.len(), reference.len());
+        assert_eq!(drain(&mut timer), drain(&mut reference));
+    }
+}
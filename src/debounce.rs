@@ -0,0 +1,276 @@
+use crate::KeyedTimer;
+use alloc::collections::VecDeque;
+use core::hash::Hash;
+
+/// Which edges of a burst of triggers fire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Edge {
+    /// Fire on the first trigger of a burst.
+    Leading,
+    /// Fire at the end of a burst.
+    #[default]
+    Trailing,
+    /// Fire on the first trigger, and again at the end if the burst had more
+    /// than one trigger.
+    Both,
+}
+
+impl Edge {
+    fn leading(self) -> bool {
+        self != Edge::Trailing
+    }
+
+    fn trailing(self) -> bool {
+        self != Edge::Leading
+    }
+}
+
+/// Fires a key once its triggers have gone quiet for `delay`.
+///
+/// Like the timers, a debouncer is driven with [`update`](Debouncer::update)
+/// and [`poll`](Debouncer::poll).
+#[derive(Debug, Clone)]
+pub struct Debouncer<K> {
+    delay: u32,
+    edge: Edge,
+    timers: KeyedTimer<K, bool>,
+    ready: VecDeque<K>,
+}
+
+impl<K: Hash + Eq + Clone> Debouncer<K> {
+    pub fn new(delay: u32, edge: Edge) -> Debouncer<K> {
+        Debouncer {
+            delay,
+            edge,
+            timers: KeyedTimer::new(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    pub fn trigger(&mut self, key: K) {
+        let pending = if self.timers.remove(&key).is_some() {
+            self.edge.trailing()
+        } else if self.edge.leading() {
+            self.ready.push_back(key.clone());
+            false
+        } else {
+            true
+        };
+
+        self.timers.insert(key, self.delay, pending);
+    }
+
+    /// Forgets any burst in progress for `key`, so its trailing edge does not
+    /// fire. Returns whether there was one.
+    pub fn cancel(&mut self, key: &K) -> bool {
+        self.ready.retain(|ready| ready != key);
+        self.timers.remove(key).is_some()
+    }
+
+    /// Returns the time until the state of some key next changes, which may
+    /// be earlier than the next key to fire.
+    pub fn next_in(&self) -> Option<u32> {
+        if self.ready.is_empty() {
+            self.timers.next_in()
+        } else {
+            Some(0)
+        }
+    }
+
+    pub fn update(&mut self, elapsed: u32) {
+        self.timers.update(elapsed);
+    }
+
+    pub fn poll(&mut self) -> Option<K> {
+        if let Some(key) = self.ready.pop_front() {
+            return Some(key);
+        }
+
+        while let Some((key, pending)) = self.timers.poll() {
+            if pending {
+                return Some(key);
+            }
+        }
+        None
+    }
+}
+
+/// Fires a key at most once per `period`, however often it is triggered.
+#[derive(Debug, Clone)]
+pub struct Throttler<K> {
+    period: u32,
+    edge: Edge,
+    timers: KeyedTimer<K, bool>,
+    ready: VecDeque<K>,
+}
+
+impl<K: Hash + Eq + Clone> Throttler<K> {
+    pub fn new(period: u32, edge: Edge) -> Throttler<K> {
+        Throttler {
+            period,
+            edge,
+            timers: KeyedTimer::new(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    pub fn trigger(&mut self, key: K) {
+        if let Some(pending) = self.timers.get_mut(&key) {
+            *pending |= self.edge.trailing();
+        } else if self.edge.leading() {
+            self.ready.push_back(key.clone());
+            self.timers.insert(key, self.period, false);
+        } else {
+            self.timers.insert(key, self.period, true);
+        }
+    }
+
+    /// Ends the period in progress for `key`, so its trailing edge does not
+    /// fire. Returns whether there was one.
+    pub fn cancel(&mut self, key: &K) -> bool {
+        self.ready.retain(|ready| ready != key);
+        self.timers.remove(key).is_some()
+    }
+
+    /// Returns the time until the state of some key next changes, which may
+    /// be earlier than the next key to fire.
+    pub fn next_in(&self) -> Option<u32> {
+        if self.ready.is_empty() {
+            self.timers.next_in()
+        } else {
+            Some(0)
+        }
+    }
+
+    pub fn update(&mut self, elapsed: u32) {
+        self.timers.update(elapsed);
+    }
+
+    pub fn poll(&mut self) -> Option<K> {
+        if let Some(key) = self.ready.pop_front() {
+            return Some(key);
+        }
+
+        while let Some((key, pending)) = self.timers.poll() {
+            if pending {
+                // A trailing fire starts the next period.
+                self.timers.insert(key.clone(), self.period, false);
+                return Some(key);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// Triggers `key` at each of the given times and returns the times at
+    /// which it fired.
+    fn run<L>(limiter: &mut L, triggers: &[u32]) -> Vec<u32>
+    where
+        L: Limiter,
+    {
+        let mut fired = Vec::new();
+        for now in 0..=100 {
+            if triggers.contains(&now) {
+                limiter.trigger();
+            }
+            while limiter.poll() {
+                fired.push(now);
+            }
+            limiter.update(1);
+        }
+        fired
+    }
+
+    trait Limiter {
+        fn trigger(&mut self);
+        fn poll(&mut self) -> bool;
+        fn update(&mut self, elapsed: u32);
+    }
+
+    impl Limiter for Debouncer<&str> {
+        fn trigger(&mut self) {
+            Debouncer::trigger(self, "key");
+        }
+
+        fn poll(&mut self) -> bool {
+            Debouncer::poll(self).is_some()
+        }
+
+        fn update(&mut self, elapsed: u32) {
+            Debouncer::update(self, elapsed);
+        }
+    }
+
+    impl Limiter for Throttler<&str> {
+        fn trigger(&mut self) {
+            Throttler::trigger(self, "key");
+        }
+
+        fn poll(&mut self) -> bool {
+            Throttler::poll(self).is_some()
+        }
+
+        fn update(&mut self, elapsed: u32) {
+            Throttler::update(self, elapsed);
+        }
+    }
+
+    const BURST: [u32; 5] = [0, 3, 6, 9, 40];
+
+    #[test]
+    fn debounce_trailing() {
+        let mut debouncer = Debouncer::new(5, Edge::Trailing);
+        assert_eq!(run(&mut debouncer, &BURST), [14, 45]);
+    }
+
+    #[test]
+    fn debounce_leading() {
+        let mut debouncer = Debouncer::new(5, Edge::Leading);
+        assert_eq!(run(&mut debouncer, &BURST), [0, 40]);
+    }
+
+    #[test]
+    fn debounce_both() {
+        let mut debouncer = Debouncer::new(5, Edge::Both);
+        assert_eq!(run(&mut debouncer, &BURST), [0, 14, 40]);
+    }
+
+    #[test]
+    fn debounce_keys_are_independent() {
+        let mut debouncer = Debouncer::new(5, Edge::Trailing);
+
+        debouncer.trigger(1);
+        debouncer.update(3);
+        debouncer.trigger(2);
+        debouncer.update(2);
+        assert_eq!(debouncer.poll(), Some(1));
+        assert_eq!(debouncer.poll(), None);
+
+        assert!(debouncer.cancel(&2));
+        debouncer.update(10);
+        assert_eq!(debouncer.poll(), None);
+    }
+
+    #[test]
+    fn throttle_trailing() {
+        let mut throttler = Throttler::new(5, Edge::Trailing);
+        assert_eq!(run(&mut throttler, &BURST), [5, 10, 45]);
+    }
+
+    #[test]
+    fn throttle_leading() {
+        let mut throttler = Throttler::new(5, Edge::Leading);
+        assert_eq!(run(&mut throttler, &BURST), [0, 6, 40]);
+    }
+
+    #[test]
+    fn throttle_both() {
+        let mut throttler = Throttler::new(5, Edge::Both);
+        assert_eq!(run(&mut throttler, &BURST), [0, 5, 10, 40]);
+    }
+}
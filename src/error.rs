@@ -0,0 +1,25 @@
+use core::error::Error;
+use core::fmt;
+
+/// The error returned by [`PullTimer::try_add`](crate::PullTimer::try_add)
+/// when a deadline is beyond the timer's horizon. It gives the event back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddError<T>(T);
+
+impl<T> AddError<T> {
+    pub(crate) fn new(event: T) -> AddError<T> {
+        AddError(event)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Display for AddError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline is beyond the timer's horizon")
+    }
+}
+
+impl<T: fmt::Debug> Error for AddError<T> {}
@@ -0,0 +1,241 @@
+use crate::TimeUnit;
+
+/// A [`PullTimer`](crate::PullTimer) that holds at most `N` events in an
+/// inline ring buffer and never allocates.
+///
+/// Adding to a full timer hands the event back instead of growing. Since
+/// [`new`](StaticPullTimer::new) is a `const fn`, the timer can be placed in
+/// a `static`.
+#[derive(Debug, Clone)]
+pub struct StaticPullTimer<T, const N: usize, D = u32> {
+    buffer: [Option<(D, T)>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize, D: TimeUnit> StaticPullTimer<T, N, D> {
+    pub const fn new() -> StaticPullTimer<T, N, D> {
+        StaticPullTimer {
+            buffer: [const { None }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn next_in(&self) -> Option<D> {
+        self.get(0).map(|&(delta, _)| delta)
+    }
+
+    pub fn update(&mut self, elapsed: D) {
+        let mut remaining = elapsed;
+        for index in 0..self.len {
+            let (delta, _) = self.get_mut(index).expect("index out of bounds");
+            let temp = *delta;
+            *delta = delta.saturating_sub(remaining);
+            remaining = remaining.saturating_sub(temp);
+
+            if remaining == D::ZERO {
+                break;
+            }
+        }
+    }
+
+    pub fn add(&mut self, deadline: D, event: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(event);
+        }
+
+        let mut sum = D::ZERO;
+        let mut insertion_point = 0;
+
+        for index in 0..self.len {
+            let &(delta, _) = self.get(index).expect("index out of bounds");
+            if sum.saturating_add(delta) > deadline {
+                break;
+            }
+            insertion_point = index + 1;
+            sum = sum.saturating_add(delta);
+        }
+
+        let insertion_delta = deadline.saturating_sub(sum);
+
+        if let Some((delta, _)) = self.get_mut(insertion_point) {
+            *delta = delta.saturating_sub(insertion_delta);
+        }
+
+        self.insert(insertion_point, (insertion_delta, event));
+        Ok(())
+    }
+
+    pub fn remove(&mut self, event: T) -> Option<D>
+    where
+        T: PartialEq,
+    {
+        let mut sum = D::ZERO;
+        let mut target = None;
+
+        for index in 0..self.len {
+            let (delta, element) = self.get(index).expect("index out of bounds");
+            sum = sum.saturating_add(*delta);
+            if *element == event {
+                target = Some(index);
+                break;
+            }
+        }
+
+        self.take(target?);
+        Some(sum)
+    }
+
+    pub fn poll(&mut self) -> Option<T> {
+        let &(delta, _) = self.get(0)?;
+
+        if delta == D::ZERO {
+            self.take(0).map(|(_, event)| event)
+        } else {
+            None
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    fn slot(&self, index: usize) -> usize {
+        (self.head + index) % N
+    }
+
+    fn get(&self, index: usize) -> Option<&(D, T)> {
+        if index < self.len {
+            self.buffer[self.slot(index)].as_ref()
+        } else {
+            None
+        }
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut (D, T)> {
+        if index < self.len {
+            self.buffer[self.slot(index)].as_mut()
+        } else {
+            None
+        }
+    }
+
+    fn insert(&mut self, index: usize, entry: (D, T)) {
+        if index == 0 {
+            self.head = (self.head + N - 1) % N;
+        } else {
+            for shifted in (index..self.len).rev() {
+                self.buffer[self.slot(shifted + 1)] = self.buffer[self.slot(shifted)].take();
+            }
+        }
+
+        self.buffer[self.slot(index)] = Some(entry);
+        self.len += 1;
+    }
+
+    fn take(&mut self, index: usize) -> Option<(D, T)> {
+        if index >= self.len {
+            return None;
+        }
+
+        let entry = self.buffer[self.slot(index)].take();
+
+        if index == 0 {
+            self.head = (self.head + 1) % N;
+        } else {
+            for shifted in index..self.len - 1 {
+                self.buffer[self.slot(shifted)] = self.buffer[self.slot(shifted + 1)].take();
+            }
+        }
+
+        self.len -= 1;
+
+        if let (Some((delta, _)), Some((next_delta, _))) = (&entry, self.get_mut(index)) {
+            *next_delta = next_delta.saturating_add(*delta);
+        }
+
+        entry
+    }
+}
+
+impl<T, const N: usize, D: TimeUnit> Default for StaticPullTimer<T, N, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EMPTY: StaticPullTimer<u8, 4> = StaticPullTimer::new();
+
+    #[test]
+    fn static_timer_is_const_constructible() {
+        assert!(EMPTY.is_empty());
+        assert_eq!(EMPTY.capacity(), 4);
+    }
+
+    #[test]
+    fn static_timer_rejects_events_when_full() {
+        let mut timer = StaticPullTimer::<_, 2>::new();
+
+        assert_eq!(timer.add(5, "a"), Ok(()));
+        assert_eq!(timer.add(1, "b"), Ok(()));
+        assert!(timer.is_full());
+        assert_eq!(timer.add(3, "c"), Err("c"));
+
+        timer.update(1);
+        assert_eq!(timer.poll(), Some("b"));
+        assert_eq!(timer.add(3, "c"), Ok(()));
+    }
+
+    #[test]
+    fn static_timer_fires_in_time() {
+        let mut timer = StaticPullTimer::<_, 5>::new();
+
+        timer.add(40, 40).unwrap();
+        timer.add(20, 20).unwrap();
+        timer.add(0, 0).unwrap();
+        timer.add(30, 30).unwrap();
+        timer.add(10, 10).unwrap();
+
+        for i in 0..=41 {
+            if let Some(value) = timer.poll() {
+                assert_eq!(value, i);
+            }
+            timer.update(1);
+        }
+        assert!(timer.is_empty());
+    }
+
+    #[test]
+    fn static_timer_wraps_around() {
+        let mut timer = StaticPullTimer::<_, 3>::new();
+
+        for round in 0..10 {
+            timer.add(2, round * 2).unwrap();
+            timer.add(1, round * 2 + 1).unwrap();
+            timer.add(3, 99).unwrap();
+
+            assert_eq!(timer.remove(99), Some(3));
+            timer.update(2);
+            assert_eq!(timer.poll(), Some(round * 2 + 1));
+            assert_eq!(timer.poll(), Some(round * 2));
+            assert_eq!(timer.poll(), None);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use alloc::vec::Vec;
+
+/// A handle to a pending timer. Handles to timers that have fired or been
+/// cancelled never match a newer timer, even if it reuses the same slot: a
+/// slot whose generation would wrap around is retired instead of reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerId {
+    index: u32,
+    generation: u32,
+}
+
+impl TimerId {
+    pub(crate) fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+/// A tag for a set of timers that are managed together, such as all the
+/// timers of one subsystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GroupId(pub u32);
+
+#[derive(Debug, Clone)]
+struct Slot<V> {
+    generation: u32,
+    value: Option<V>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Slots<V> {
+    slots: Vec<Slot<V>>,
+    free: Vec<u32>,
+    retired: usize,
+}
+
+impl<V> Slots<V> {
+    pub(crate) const fn new() -> Slots<V> {
+        Slots {
+            slots: Vec::new(),
+            free: Vec::new(),
+            retired: 0,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, value: V) -> TimerId {
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.value = Some(value);
+                TimerId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("too many timers");
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                });
+                TimerId {
+                    index,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    pub(crate) fn get(&self, id: TimerId) -> Option<&V> {
+        let slot = self.slots.get(id.index as usize)?;
+        if slot.generation == id.generation {
+            slot.value.as_ref()
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, id: TimerId) -> Option<&mut V> {
+        let slot = self.slots.get_mut(id.index as usize)?;
+        if slot.generation == id.generation {
+            slot.value.as_mut()
+        } else {
+            None
+        }
+    }
+
+    /// Yields every slot's value by index, or `None` for free slots.
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = Option<&mut V>> {
+        self.slots.iter_mut().map(|slot| slot.value.as_mut())
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.slots.len() - self.free.len() - self.retired
+    }
+
+    pub(crate) fn contains(&self, id: TimerId) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub(crate) fn remove(&mut self, id: TimerId) -> Option<V> {
+        let slot = self.slots.get_mut(id.index as usize)?;
+        if slot.generation != id.generation {
+            return None;
+        }
+        let value = slot.value.take()?;
+        match slot.generation.checked_add(1) {
+            Some(generation) => {
+                slot.generation = generation;
+                self.free.push(id.index);
+            }
+            // Reusing the slot would let stale handles match again.
+            None => self.retired += 1,
+        }
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_retire_instead_of_wrapping_the_generation() {
+        let mut slots = Slots::new();
+        let first = slots.insert("first");
+        slots.slots[first.index()].generation = u32::MAX;
+        let stale = TimerId {
+            index: first.index,
+            generation: u32::MAX,
+        };
+
+        assert_eq!(slots.remove(stale), Some("first"));
+        assert_eq!(slots.len(), 0);
+
+        let second = slots.insert("second");
+        assert_ne!(second.index, stale.index);
+        assert_eq!(slots.get(stale), None);
+        assert_eq!(slots.remove(stale), None);
+        assert_eq!(slots.len(), 1);
+    }
+}
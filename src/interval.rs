@@ -0,0 +1,99 @@
+use crate::{GroupId, TimeUnit};
+
+/// What a repeating timer does when it is late by a whole period or more.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissedTicks {
+    /// Fire once for every missed period, as soon as possible, then carry on
+    /// with the original schedule.
+    #[default]
+    Burst,
+    /// Fire once, then carry on with the original schedule from the next
+    /// period that is still in the future.
+    Skip,
+    /// Fire once, then start a new schedule one period from now.
+    Delay,
+}
+
+/// The schedule of a repeating timer, for
+/// [`PullTimer::add_interval_with`](crate::PullTimer::add_interval_with).
+///
+/// Each repetition is scheduled relative to the deadline it should have
+/// fired at, not the time it was polled, so polling late does not drift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval<D = u32> {
+    pub(crate) period: D,
+    pub(crate) delay: D,
+    pub(crate) times: Option<u32>,
+    pub(crate) missed: MissedTicks,
+    pub(crate) group: Option<GroupId>,
+}
+
+impl<D: TimeUnit> Interval<D> {
+    /// An interval that first fires after one `period` and repeats forever.
+    pub fn new(period: D) -> Interval<D> {
+        Interval {
+            period,
+            delay: period,
+            times: None,
+            missed: MissedTicks::default(),
+            group: None,
+        }
+    }
+
+    /// Sets the time until the first firing.
+    pub fn delay(self, delay: D) -> Interval<D> {
+        Interval { delay, ..self }
+    }
+
+    /// Stops the interval after it has fired `times` times.
+    pub fn times(self, times: u32) -> Interval<D> {
+        Interval {
+            times: Some(times),
+            ..self
+        }
+    }
+
+    pub fn missed(self, missed: MissedTicks) -> Interval<D> {
+        Interval { missed, ..self }
+    }
+
+    /// Puts the timer in `group`, as
+    /// [`add_to_group`](crate::PullTimer::add_to_group) does.
+    pub fn group(self, group: GroupId) -> Interval<D> {
+        Interval {
+            group: Some(group),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Repeat<T, D> {
+    pub(crate) period: D,
+    pub(crate) left: Option<u32>,
+    pub(crate) missed: MissedTicks,
+    pub(crate) clone: fn(&T) -> T,
+}
+
+impl<T, D: TimeUnit> Repeat<T, D> {
+    /// Returns the deadline and lateness of the next firing, given how late
+    /// the current one was, or `None` if the interval is done.
+    pub(crate) fn next(&mut self, late: D) -> Option<(D, D)> {
+        match &mut self.left {
+            Some(1) => return None,
+            Some(left) => *left -= 1,
+            None => {}
+        }
+
+        let period = self.period;
+        Some(if late < period {
+            (period.saturating_sub(late), D::ZERO)
+        } else {
+            match self.missed {
+                MissedTicks::Burst => (D::ZERO, late.saturating_sub(period)),
+                MissedTicks::Skip => (period.saturating_sub(late.rem(period)), D::ZERO),
+                MissedTicks::Delay => (period, D::ZERO),
+            }
+        })
+    }
+}
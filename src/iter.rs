@@ -0,0 +1,181 @@
+use crate::{PullTimer, TimeUnit, TimerId};
+use alloc::collections::btree_set;
+use alloc::vec::{self, Vec};
+use core::iter::FusedIterator;
+
+/// An iterator over the pending events of a [`PullTimer`] in firing order,
+/// along with the time left until each fires. Paused events come last.
+#[derive(Debug)]
+pub struct Iter<'a, T, D = u32> {
+    timer: &'a PullTimer<T, D>,
+    cursor: Option<TimerId>,
+    paused: btree_set::Iter<'a, TimerId>,
+    left: usize,
+    sum: D,
+}
+
+impl<'a, T, D: TimeUnit> Iter<'a, T, D> {
+    pub(crate) fn new(timer: &'a PullTimer<T, D>) -> Iter<'a, T, D> {
+        Iter {
+            timer,
+            cursor: timer.head,
+            paused: timer.paused.iter(),
+            left: timer.len(),
+            sum: D::ZERO,
+        }
+    }
+}
+
+// Not derived, since that would require `T: Clone`.
+impl<T, D: Copy> Clone for Iter<'_, T, D> {
+    fn clone(&self) -> Self {
+        Iter {
+            timer: self.timer,
+            cursor: self.cursor,
+            paused: self.paused.clone(),
+            left: self.left,
+            sum: self.sum,
+        }
+    }
+}
+
+impl<'a, T, D: TimeUnit> Iterator for Iter<'a, T, D> {
+    type Item = (D, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(id) = self.cursor else {
+            let entry = self.timer.entry(*self.paused.next()?);
+            self.left -= 1;
+            return Some((entry.delta, &entry.event));
+        };
+
+        let entry = self.timer.entry(id);
+        self.cursor = entry.next;
+        self.left -= 1;
+        self.sum = self.sum.saturating_add(entry.delta);
+        Some((self.sum, &entry.event))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.left, Some(self.left))
+    }
+}
+
+impl<T, D: TimeUnit> ExactSizeIterator for Iter<'_, T, D> {}
+
+impl<T, D: TimeUnit> FusedIterator for Iter<'_, T, D> {}
+
+/// A mutable iterator over the pending events of a [`PullTimer`] in firing
+/// order, along with the time left until each fires. Paused events come
+/// last.
+#[derive(Debug)]
+pub struct IterMut<'a, T, D = u32> {
+    inner: vec::IntoIter<(D, &'a mut T)>,
+}
+
+impl<'a, T, D: TimeUnit> IterMut<'a, T, D> {
+    pub(crate) fn new(timer: &'a mut PullTimer<T, D>) -> IterMut<'a, T, D> {
+        let order = timer
+            .iter_all()
+            .map(|(remaining, id, _)| (remaining, id))
+            .collect::<Vec<_>>();
+
+        // Hand out each entry's event in firing order, taking it from the
+        // slots by index so that every event is borrowed only once.
+        let mut entries = timer.entries.values_mut().collect::<Vec<_>>();
+        let inner = order
+            .into_iter()
+            .map(|(remaining, id)| {
+                let entry = entries[id.index()].take().expect("linked timers exist");
+                (remaining, &mut entry.event)
+            })
+            .collect::<Vec<_>>();
+
+        IterMut {
+            inner: inner.into_iter(),
+        }
+    }
+}
+
+impl<'a, T, D: TimeUnit> Iterator for IterMut<'a, T, D> {
+    type Item = (D, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, D: TimeUnit> ExactSizeIterator for IterMut<'_, T, D> {}
+
+impl<T, D: TimeUnit> FusedIterator for IterMut<'_, T, D> {}
+
+/// An owning iterator over the pending events of a [`PullTimer`] in firing
+/// order, along with the time left until each would have fired. Paused
+/// events come last.
+#[derive(Debug, Clone)]
+pub struct IntoIter<T, D = u32> {
+    timer: PullTimer<T, D>,
+    sum: D,
+}
+
+impl<T, D: TimeUnit> Iterator for IntoIter<T, D> {
+    type Item = (D, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(id) = self.timer.head else {
+            let id = *self.timer.paused.first()?;
+            let entry = self.timer.take(id)?;
+            return Some((entry.delta, entry.event));
+        };
+
+        let entry = self.timer.entry_mut(id);
+        self.sum = self.sum.saturating_add(entry.delta);
+        // Already counted, so it must not be folded into the next entry.
+        entry.delta = D::ZERO;
+        let entry = self.timer.take(id)?;
+        Some((self.sum, entry.event))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.timer.len();
+        (left, Some(left))
+    }
+}
+
+impl<T, D: TimeUnit> ExactSizeIterator for IntoIter<T, D> {}
+
+impl<T, D: TimeUnit> FusedIterator for IntoIter<T, D> {}
+
+impl<T, D: TimeUnit> IntoIterator for PullTimer<T, D> {
+    type Item = (D, T);
+    type IntoIter = IntoIter<T, D>;
+
+    fn into_iter(self) -> IntoIter<T, D> {
+        IntoIter {
+            timer: self,
+            sum: D::ZERO,
+        }
+    }
+}
+
+impl<'a, T, D: TimeUnit> IntoIterator for &'a PullTimer<T, D> {
+    type Item = (D, &'a T);
+    type IntoIter = Iter<'a, T, D>;
+
+    fn into_iter(self) -> Iter<'a, T, D> {
+        self.iter()
+    }
+}
+
+impl<'a, T, D: TimeUnit> IntoIterator for &'a mut PullTimer<T, D> {
+    type Item = (D, &'a mut T);
+    type IntoIter = IterMut<'a, T, D>;
+
+    fn into_iter(self) -> IterMut<'a, T, D> {
+        self.iter_mut()
+    }
+}
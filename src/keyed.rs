@@ -0,0 +1,148 @@
+use crate::{PullTimer, TimeUnit, TimerId};
+use core::borrow::Borrow;
+use core::hash::Hash;
+use std::collections::HashMap;
+
+/// A timer that holds at most one pending event per key.
+///
+/// Events are kept in a [`PullTimer`] and found through a map from each key
+/// to its [`TimerId`], so they never need to be compared with each other.
+/// Removing, reading and changing the event of a key take constant time.
+/// Inserting and [`remaining`](KeyedTimer::remaining) walk the events due
+/// before the key's, as the underlying list of deltas requires.
+#[derive(Debug, Clone)]
+pub struct KeyedTimer<K, T, D = u32> {
+    timer: PullTimer<(K, T), D>,
+    ids: HashMap<K, TimerId>,
+}
+
+impl<K: Hash + Eq + Clone, T> KeyedTimer<K, T> {
+    pub fn new() -> KeyedTimer<K, T> {
+        KeyedTimer::default()
+    }
+}
+
+impl<K: Hash + Eq + Clone, T, D: TimeUnit> KeyedTimer<K, T, D> {
+    pub fn next_in(&self) -> Option<D> {
+        self.timer.next_in()
+    }
+
+    pub fn update(&mut self, elapsed: D) {
+        self.timer.update(elapsed);
+    }
+
+    /// Sets the timer for `key`, replacing and returning the value of any
+    /// timer it already had.
+    pub fn insert(&mut self, key: K, deadline: D, value: T) -> Option<T> {
+        let previous = self.remove(&key);
+        let id = self.timer.add(deadline, (key.clone(), value));
+        self.ids.insert(key, id);
+        previous
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<T>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let id = self.ids.remove(key)?;
+        self.timer.cancel(id).map(|(_, value)| value)
+    }
+
+    pub fn remaining<Q>(&self, key: &Q) -> Option<D>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.timer.remaining(*self.ids.get(key)?)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&T>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (_, value) = self.timer.get(*self.ids.get(key)?)?;
+        Some(value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut T>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (_, value) = self.timer.get_mut(*self.ids.get(key)?)?;
+        Some(value)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn poll(&mut self) -> Option<(K, T)> {
+        let (key, value) = self.timer.poll()?;
+        self.ids.remove(&key);
+        Some((key, value))
+    }
+}
+
+impl<K, T, D: TimeUnit> Default for KeyedTimer<K, T, D> {
+    fn default() -> Self {
+        KeyedTimer {
+            timer: PullTimer::default(),
+            ids: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn keyed_timer_replaces_timer_for_key() {
+        let mut timer = KeyedTimer::new();
+
+        assert_eq!(timer.insert("alice", 10, "idle"), None);
+        assert_eq!(timer.insert("bob", 15, "idle"), None);
+        timer.update(5);
+        assert_eq!(timer.insert("alice", 20, "reset"), Some("idle"));
+
+        assert_eq!(timer.len(), 2);
+        assert_eq!(timer.remaining("alice"), Some(20));
+        assert_eq!(timer.get("alice"), Some(&"reset"));
+        assert_eq!(timer.next_in(), Some(10));
+
+        timer.update(10);
+        assert_eq!(timer.poll(), Some(("bob", "idle")));
+        assert_eq!(timer.poll(), None);
+        assert!(!timer.contains_key("bob"));
+
+        timer.update(10);
+        assert_eq!(timer.poll(), Some(("alice", "reset")));
+        assert!(timer.is_empty());
+    }
+
+    #[test]
+    fn keyed_timer_remove() {
+        let mut timer = KeyedTimer::new();
+
+        timer.insert(String::from("session"), 30, ());
+        assert_eq!(timer.remove("session"), Some(()));
+        assert_eq!(timer.remove("session"), None);
+        assert_eq!(timer.next_in(), None);
+    }
+}
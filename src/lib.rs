@@ -1,85 +1,778 @@
 #![doc = include_str!("../README.md")]
+#![no_std]
 
-use std::collections::VecDeque;
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+mod callback;
+#[cfg(feature = "std")]
+mod clock;
+pub mod conformance;
+#[cfg(feature = "std")]
+mod debounce;
+mod error;
+mod fixed;
+mod id;
+mod interval;
+pub mod iter;
+#[cfg(feature = "std")]
+mod keyed;
+mod scaled;
+pub mod sim;
+mod time;
+mod timeline;
+mod timer;
+mod tree;
+mod wheel;
+
+pub use callback::CallbackTimer;
+#[cfg(feature = "std")]
+pub use clock::{Clock, ClockedTimer, ManualClock, StdClock};
+#[cfg(feature = "std")]
+pub use debounce::{Debouncer, Edge, Throttler};
+pub use error::AddError;
+pub use fixed::StaticPullTimer;
+pub use id::{GroupId, TimerId};
+pub use interval::{Interval, MissedTicks};
+#[cfg(feature = "std")]
+pub use keyed::KeyedTimer;
+pub use scaled::ScaledTimer;
+pub use time::TimeUnit;
+pub use timeline::{TimelineId, Timelines};
+pub use timer::Timer;
+pub use tree::TreeTimer;
+pub use wheel::WheelTimer;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use id::Slots;
+use interval::Repeat;
+use iter::{Iter, IterMut};
+
+#[derive(Debug, Clone)]
+struct Entry<T, D> {
+    delta: D,
+    late: D,
+    event: T,
+    group: Option<GroupId>,
+    repeat: Option<Repeat<T, D>>,
+    paused: bool,
+    prev: Option<TimerId>,
+    next: Option<TimerId>,
+}
+
+/// An event that has fired, along with how long ago its deadline passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expired<T, D = u32> {
+    pub id: TimerId,
+    pub event: T,
+    pub lateness: D,
+}
+
+/// A timer that keeps its events in a list of deltas, each relative to the
+/// event before it.
+///
+/// Time is counted in `D`, which defaults to `u32`. Other units can be
+/// chosen through [`TimeUnit`], e.g. `PullTimer::<T, Duration>::default()`.
+///
+/// No deadline may be further away than the timer's horizon, which is
+/// `D::MAX` unless set lower with [`set_horizon`](PullTimer::set_horizon).
+/// This bounds the sum of all deltas, so they can never overflow.
+///
+/// Entries are linked into the list through their [`TimerId`]s, so looking
+/// up, changing or cancelling a timer by id never searches the list. Only
+/// adding a timer and asking for the time it has left walk the entries
+/// before it.
 #[derive(Debug, Clone)]
-pub struct PullTimer<T>(VecDeque<(u32, T)>);
+pub struct PullTimer<T, D = u32> {
+    entries: Slots<Entry<T, D>>,
+    head: Option<TimerId>,
+    paused: BTreeSet<TimerId>,
+    groups: BTreeMap<GroupId, BTreeSet<TimerId>>,
+    horizon: D,
+    now: D,
+}
 
 impl<T> PullTimer<T> {
     pub fn new() -> PullTimer<T> {
-        PullTimer(VecDeque::new())
+        PullTimer::default()
     }
+}
 
-    pub fn next_in(&self) -> Option<u32> {
-        self.0.front().map(|&(deadline, _)| deadline)
+impl<T, D: TimeUnit> PullTimer<T, D> {
+    pub fn next_in(&self) -> Option<D> {
+        self.head.map(|id| self.entry(id).delta)
     }
 
-    pub fn update(&mut self, elapsed: u32) {
-        let mut remaining = elapsed;
-        for (delta, _) in &mut self.0 {
-            let temp = *delta;
-            *delta = delta.saturating_sub(elapsed);
-            remaining = remaining.saturating_sub(temp);
+    pub fn update(&mut self, elapsed: D) {
+        self.now = self.now.saturating_add(elapsed);
 
-            if remaining == 0 {
+        let mut remaining = elapsed;
+        let mut cursor = self.head;
+        while let Some(id) = cursor {
+            let entry = self.entry_mut(id);
+            if entry.delta > remaining {
+                entry.delta = entry.delta.saturating_sub(remaining);
                 break;
             }
+
+            remaining = remaining.saturating_sub(entry.delta);
+            entry.delta = D::ZERO;
+            entry.late = entry.late.saturating_add(remaining);
+            cursor = entry.next;
+        }
+    }
+
+    /// Updates the timer, then yields every event that is due, in firing
+    /// order. Events left in the iterator when it is dropped stay due.
+    pub fn advance(&mut self, elapsed: D) -> impl Iterator<Item = Expired<T, D>> + '_ {
+        self.update(elapsed);
+        core::iter::from_fn(move || self.poll_expired())
+    }
+
+    /// Returns the total time the timer has been updated by.
+    ///
+    /// Absolute time saturates at `D::MAX`. Past that point the clock stops,
+    /// so [`advance_to`](PullTimer::advance_to), [`add_at`](PullTimer::add_at)
+    /// and [`deadline_of`](PullTimer::deadline_of) no longer make sense. With
+    /// `u32` milliseconds that happens after about 49.7 days; use `u64` for
+    /// timers that run longer.
+    pub fn now(&self) -> D {
+        self.now
+    }
+
+    /// Updates the timer up to the absolute time `now`. Times in the past
+    /// are ignored, since time never runs backwards.
+    pub fn advance_to(&mut self, now: D) {
+        if now > self.now {
+            self.update(now.saturating_sub(self.now));
         }
     }
 
-    pub fn add(&mut self, deadline: u32, event: T) {
-        let mut sum = 0;
-        let mut insertion_point = 0;
+    /// Updates the timer by exactly the time until the next event, then
+    /// returns that event along with the time skipped.
+    pub fn advance_to_next(&mut self) -> Option<(D, T)> {
+        let skipped = self.next_in()?;
+        self.update(skipped);
+        self.poll().map(|event| (skipped, event))
+    }
+
+    /// Jumps from event to event in firing order, passing each to `handle`,
+    /// until the absolute time `limit`. The timer is then left at `limit`.
+    ///
+    /// `handle` gets the timer itself, so it can read [`now`](PullTimer::now)
+    /// and schedule or cancel further events. Returns the number of events
+    /// handled.
+    pub fn run_until<F>(&mut self, limit: D, handle: F) -> usize
+    where
+        F: FnMut(&mut Self, T),
+    {
+        PullTimer::run_until_in(self, |timer| timer, limit, handle)
+    }
+
+    /// Runs [`run_until`](PullTimer::run_until) on the timer `timer` finds in
+    /// `owner`, handing `owner` to `handle` so it can reach the rest of it.
+    pub(crate) fn run_until_in<S, F>(
+        owner: &mut S,
+        timer: fn(&mut S) -> &mut Self,
+        limit: D,
+        mut handle: F,
+    ) -> usize
+    where
+        F: FnMut(&mut S, T),
+    {
+        let mut handled = 0;
 
-        for (index, &(delta, _)) in self.0.iter().enumerate() {
-            if sum + delta > deadline {
+        while let Some(next) = timer(owner).next_in() {
+            if timer(owner).now.saturating_add(next) > limit {
                 break;
             }
-            insertion_point = index + 1;
-            sum += delta;
+            let (_, event) = timer(owner).advance_to_next().expect("an event is pending");
+            handle(owner, event);
+            handled += 1;
+        }
+
+        timer(owner).advance_to(limit);
+        handled
+    }
+
+    pub fn horizon(&self) -> D {
+        self.horizon
+    }
+
+    pub fn set_horizon(&mut self, horizon: D) {
+        self.horizon = horizon;
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `deadline` is beyond the timer's horizon. See
+    /// [`try_add`](PullTimer::try_add) for a fallible version.
+    pub fn add(&mut self, deadline: D, event: T) -> TimerId {
+        match self.try_add(deadline, event) {
+            Ok(id) => id,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    pub fn try_add(&mut self, deadline: D, event: T) -> Result<TimerId, AddError<T>> {
+        if deadline > self.horizon {
+            return Err(AddError::new(event));
+        }
+
+        Ok(self.schedule(deadline, D::ZERO, event, None, None))
+    }
+
+    /// Adds an event as part of `group`, so it can be found or cancelled
+    /// along with the rest of the group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `deadline` is beyond the timer's horizon. See
+    /// [`try_add_to_group`](PullTimer::try_add_to_group) for a fallible
+    /// version.
+    pub fn add_to_group(&mut self, group: GroupId, deadline: D, event: T) -> TimerId {
+        match self.try_add_to_group(group, deadline, event) {
+            Ok(id) => id,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    pub fn try_add_to_group(
+        &mut self,
+        group: GroupId,
+        deadline: D,
+        event: T,
+    ) -> Result<TimerId, AddError<T>> {
+        if deadline > self.horizon {
+            return Err(AddError::new(event));
         }
 
-        let insertion_delta = deadline - sum;
+        Ok(self.schedule(deadline, D::ZERO, event, Some(group), None))
+    }
 
-        if let Some((delta, _)) = &mut self.0.get_mut(insertion_point) {
-            *delta = delta.saturating_sub(insertion_delta);
+    /// Adds an event due at the absolute time `deadline`. A deadline in the
+    /// past is due at once, and is reported as late by however long ago it
+    /// was.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `deadline` is beyond the timer's horizon.
+    pub fn add_at(&mut self, deadline: D, event: T) -> TimerId {
+        if deadline < self.now {
+            let late = self.now.saturating_sub(deadline);
+            return self.schedule(D::ZERO, late, event, None, None);
         }
 
-        self.0.insert(insertion_point, (insertion_delta, event));
+        self.add(deadline.saturating_sub(self.now), event)
+    }
+
+    pub fn add_interval(&mut self, period: D, event: T) -> TimerId
+    where
+        T: Clone,
+    {
+        self.add_interval_with(Interval::new(period), event)
+    }
+
+    /// Adds an event that fires repeatedly on the given schedule. Each
+    /// firing is a clone of `event`, and all of them share one [`TimerId`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is zero, if the interval is set to fire zero
+    /// times, or if the period or first delay is beyond the horizon.
+    pub fn add_interval_with(&mut self, interval: Interval<D>, event: T) -> TimerId
+    where
+        T: Clone,
+    {
+        assert!(interval.period != D::ZERO, "period must not be zero");
+        assert!(
+            interval.times != Some(0),
+            "interval must fire at least once"
+        );
+        assert!(
+            interval.period <= self.horizon && interval.delay <= self.horizon,
+            "deadline is beyond the timer's horizon"
+        );
+
+        let repeat = Repeat {
+            period: interval.period,
+            left: interval.times,
+            missed: interval.missed,
+            clone: T::clone,
+        };
+        self.schedule(interval.delay, D::ZERO, event, interval.group, Some(repeat))
     }
 
-    pub fn remove(&mut self, event: T) -> Option<u32>
+    pub fn remove(&mut self, event: T) -> Option<D>
     where
         T: PartialEq,
     {
-        let mut sum = 0;
-        let mut target = None;
+        let (remaining, id, _) = self.iter_all().find(|(_, _, entry)| entry.event == event)?;
+
+        self.take(id);
+        Some(remaining)
+    }
+
+    /// Keeps only the events for which `keep` returns `true`, given the time
+    /// left until each fires.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(D, &T) -> bool,
+    {
+        self.split(|remaining, entry| !keep(remaining, &entry.event), |_, _| {});
+    }
+
+    /// Removes the events for which `extract` returns `true`, given the time
+    /// left until each fires, and returns them with that time in the order
+    /// of [`iter`](PullTimer::iter).
+    pub fn extract_if<F>(&mut self, mut extract: F) -> Vec<(D, T)>
+    where
+        F: FnMut(D, &T) -> bool,
+    {
+        let mut extracted = Vec::new();
+        self.split(
+            |remaining, entry| extract(remaining, &entry.event),
+            |remaining, event| extracted.push((remaining, event)),
+        );
+        extracted
+    }
+
+    /// Cancels every timer in `group`, paused or not, returning how many
+    /// there were. Only the group's own timers are visited.
+    pub fn cancel_group(&mut self, group: GroupId) -> usize {
+        self.drain_group(group).len()
+    }
+
+    /// Removes every timer in `group`, paused or not, and returns their ids
+    /// and events in no particular order. Only the group's own timers are
+    /// visited.
+    pub fn drain_group(&mut self, group: GroupId) -> Vec<(TimerId, T)> {
+        let members = self.groups.remove(&group).unwrap_or_default();
+
+        members
+            .into_iter()
+            .map(|id| {
+                let entry = self.take(id).expect("grouped timers exist");
+                (id, entry.event)
+            })
+            .collect()
+    }
+
+    pub fn len_group(&self, group: GroupId) -> usize {
+        self.groups.get(&group).map_or(0, BTreeSet::len)
+    }
+
+    /// Returns the time until the next timer in `group` fires.
+    ///
+    /// This is not indexed: it walks the running timers from the front
+    /// until it reaches the group's first one, so it takes time linear in
+    /// that timer's position.
+    pub fn next_in_group(&self, group: GroupId) -> Option<D> {
+        if self.len_group(group) == 0 {
+            return None;
+        }
+        self.iter_entries()
+            .find(|(_, _, entry)| entry.group == Some(group))
+            .map(|(remaining, _, _)| remaining)
+    }
+
+    pub fn cancel(&mut self, id: TimerId) -> Option<T> {
+        self.take(id).map(|entry| entry.event)
+    }
+
+    /// Stops a timer from counting down until it is resumed. A paused timer
+    /// does not fire and is not counted by [`next_in`](PullTimer::next_in),
+    /// but is still counted by [`len`](PullTimer::len) and its group, and
+    /// is visited by iteration, [`retain`](PullTimer::retain) and the like.
+    ///
+    /// Returns `false` if the timer is not running.
+    pub fn pause(&mut self, id: TimerId) -> bool {
+        match self.entries.get(id) {
+            Some(entry) if !entry.paused => {}
+            _ => return false,
+        }
+
+        let remaining = self.offset(id);
+        self.unlink(id);
+
+        let entry = self.entry_mut(id);
+        entry.delta = remaining;
+        entry.paused = true;
+        self.paused.insert(id);
+        true
+    }
+
+    /// Restarts a paused timer with the time it had left when it was paused.
+    ///
+    /// Returns `false` if the timer is not paused.
+    pub fn resume(&mut self, id: TimerId) -> bool {
+        if !self.paused.remove(&id) {
+            return false;
+        }
+
+        let entry = self.entry_mut(id);
+        entry.paused = false;
+        let remaining = entry.delta;
+        self.link(id, remaining);
+        true
+    }
+
+    pub fn is_paused(&self, id: TimerId) -> bool {
+        self.entries.get(id).is_some_and(|entry| entry.paused)
+    }
+
+    /// Moves a timer to a new deadline, returning the time it had left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `deadline` is beyond the timer's horizon.
+    pub fn reschedule(&mut self, id: TimerId, deadline: D) -> Option<D> {
+        assert!(
+            deadline <= self.horizon,
+            "deadline is beyond the timer's horizon"
+        );
 
-        for (index, &(delta, ref element)) in self.0.iter().enumerate() {
-            sum += delta;
-            if *element == event {
-                target = Some(index);
+        let entry = self.entries.get_mut(id)?;
+        entry.late = D::ZERO;
+        if entry.paused {
+            return Some(core::mem::replace(&mut entry.delta, deadline));
+        }
+
+        let previous = self.offset(id);
+        self.unlink(id);
+        self.link(id, deadline);
+
+        Some(previous)
+    }
+
+    /// Pushes a timer's deadline back by `extra`, returning the time it had
+    /// left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new deadline is beyond the timer's horizon.
+    pub fn postpone(&mut self, id: TimerId, extra: D) -> Option<D> {
+        let remaining = self.remaining(id)?;
+        self.reschedule(id, remaining.saturating_add(extra))
+    }
+
+    /// Brings a timer's deadline forward by `by`, but no earlier than now,
+    /// returning the time it had left.
+    pub fn expedite(&mut self, id: TimerId, by: D) -> Option<D> {
+        let remaining = self.remaining(id)?;
+        self.reschedule(id, remaining.saturating_sub(by))
+    }
+
+    pub fn contains(&self, id: TimerId) -> bool {
+        self.entries.contains(id)
+    }
+
+    /// Returns the time left until a timer fires. This walks the entries due
+    /// before it.
+    pub fn remaining(&self, id: TimerId) -> Option<D> {
+        let entry = self.entries.get(id)?;
+        if entry.paused {
+            return Some(entry.delta);
+        }
+
+        Some(self.offset(id))
+    }
+
+    /// Returns the absolute time a running timer is due at. Paused timers
+    /// have no deadline until they are resumed.
+    pub fn deadline_of(&self, id: TimerId) -> Option<D> {
+        let entry = self.entries.get(id)?;
+        if entry.paused {
+            return None;
+        }
+
+        let remaining = self.offset(id);
+        if remaining == D::ZERO {
+            Some(self.now.saturating_sub(entry.late))
+        } else {
+            Some(self.now.saturating_add(remaining))
+        }
+    }
+
+    pub fn get(&self, id: TimerId) -> Option<&T> {
+        self.entries.get(id).map(|entry| &entry.event)
+    }
+
+    pub fn get_mut(&mut self, id: TimerId) -> Option<&mut T> {
+        self.entries.get_mut(id).map(|entry| &mut entry.event)
+    }
+
+    /// Returns the next event to fire and the time left until it does.
+    pub fn peek(&self) -> Option<(&T, D)> {
+        let entry = self.entry(self.head?);
+        Some((&entry.event, entry.delta))
+    }
+
+    /// Iterates over the running timers in firing order, then the paused
+    /// ones, along with the time each has left.
+    pub fn iter(&self) -> Iter<'_, T, D> {
+        Iter::new(self)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, D> {
+        IterMut::new(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn poll(&mut self) -> Option<T> {
+        self.poll_expired().map(|expired| expired.event)
+    }
+
+    pub fn poll_expired(&mut self) -> Option<Expired<T, D>> {
+        let id = self.head?;
+        if self.entry(id).delta != D::ZERO {
+            return None;
+        }
+
+        self.unlink(id);
+        let entry = self.entry_mut(id);
+        let lateness = entry.late;
+
+        let next = match &mut entry.repeat {
+            Some(repeat) => repeat.next(lateness).map(|next| (next, repeat.clone)),
+            None => None,
+        };
+
+        let event = match next {
+            Some(((deadline, late), clone)) => {
+                let event = clone(&entry.event);
+                entry.late = late;
+                self.link(id, deadline);
+                event
+            }
+            None => self.release(id).event,
+        };
+
+        Some(Expired {
+            id,
+            event,
+            lateness,
+        })
+    }
+
+    /// Returns how late the next event is, if it is due.
+    pub(crate) fn overdue_by(&self) -> Option<D> {
+        let entry = self.entry(self.head?);
+        (entry.delta == D::ZERO).then_some(entry.late)
+    }
+
+    fn entry(&self, id: TimerId) -> &Entry<T, D> {
+        self.entries.get(id).expect("linked timers exist")
+    }
+
+    fn entry_mut(&mut self, id: TimerId) -> &mut Entry<T, D> {
+        self.entries.get_mut(id).expect("linked timers exist")
+    }
+
+    /// Returns the time left until a running timer fires, by summing the
+    /// deltas back to the front of the list.
+    fn offset(&self, id: TimerId) -> D {
+        let mut sum = D::ZERO;
+        let mut cursor = Some(id);
+        while let Some(id) = cursor {
+            let entry = self.entry(id);
+            sum = sum.saturating_add(entry.delta);
+            cursor = entry.prev;
+        }
+        sum
+    }
+
+    fn schedule(
+        &mut self,
+        deadline: D,
+        late: D,
+        event: T,
+        group: Option<GroupId>,
+        repeat: Option<Repeat<T, D>>,
+    ) -> TimerId {
+        let id = self.entries.insert(Entry {
+            delta: D::ZERO,
+            late,
+            event,
+            group,
+            repeat,
+            paused: false,
+            prev: None,
+            next: None,
+        });
+        if let Some(group) = group {
+            self.groups.entry(group).or_default().insert(id);
+        }
+
+        self.link(id, deadline);
+        id
+    }
+
+    /// Yields each running entry in firing order with its id and the time
+    /// left until it fires.
+    fn iter_entries(&self) -> impl Iterator<Item = (D, TimerId, &Entry<T, D>)> {
+        let mut sum = D::ZERO;
+        let mut cursor = self.head;
+        core::iter::from_fn(move || {
+            let id = cursor?;
+            let entry = self.entry(id);
+            sum = sum.saturating_add(entry.delta);
+            cursor = entry.next;
+            Some((sum, id, entry))
+        })
+    }
+
+    /// Yields every entry like [`iter`](PullTimer::iter) does, with its id.
+    fn iter_all(&self) -> impl Iterator<Item = (D, TimerId, &Entry<T, D>)> {
+        let paused = self.paused.iter().map(|&id| {
+            let entry = self.entry(id);
+            (entry.delta, id, entry)
+        });
+        self.iter_entries().chain(paused)
+    }
+
+    /// Removes every entry matching `predicate` in one pass, passing each to
+    /// `removed`.
+    fn split<P, R>(&mut self, mut predicate: P, mut removed: R)
+    where
+        P: FnMut(D, &Entry<T, D>) -> bool,
+        R: FnMut(D, T),
+    {
+        // Removed deltas are folded into the next entry, so `before` only
+        // counts the entries that stay.
+        let mut before = D::ZERO;
+        let mut cursor = self.head;
+
+        while let Some(id) = cursor {
+            let entry = self.entry(id);
+            let remaining = before.saturating_add(entry.delta);
+            cursor = entry.next;
+
+            if predicate(remaining, entry) {
+                removed(remaining, self.release_linked(id).event);
+            } else {
+                before = remaining;
+            }
+        }
+
+        let paused = self.paused.iter().copied().collect::<Vec<_>>();
+        for id in paused {
+            let entry = self.entry(id);
+            if predicate(entry.delta, entry) {
+                let entry = self.release(id);
+                removed(entry.delta, entry.event);
+            }
+        }
+    }
+
+    /// Links the entry of `id` into the list at `deadline`, after every
+    /// entry due at the same time unless they are less overdue than it.
+    fn link(&mut self, id: TimerId, deadline: D) {
+        let late = self.entry(id).late;
+        let mut sum = D::ZERO;
+        let mut prev = None;
+        let mut cursor = self.head;
+
+        while let Some(other) = cursor {
+            let entry = self.entry(other);
+            let next = sum.saturating_add(entry.delta);
+            if next > deadline || (next == deadline && entry.late < late) {
                 break;
             }
+            sum = next;
+            prev = cursor;
+            cursor = entry.next;
         }
 
-        let index = target?;
-        let (delta, _) = self.0.remove(index)?;
+        let delta = deadline.saturating_sub(sum);
 
-        if let Some((next_delta, _)) = self.0.get_mut(index) {
-            *next_delta += delta;
+        if let Some(next) = cursor {
+            let next = self.entry_mut(next);
+            next.delta = next.delta.saturating_sub(delta);
+            next.prev = Some(id);
+        }
+        match prev {
+            Some(prev) => self.entry_mut(prev).next = Some(id),
+            None => self.head = Some(id),
         }
 
-        Some(sum)
+        let entry = self.entry_mut(id);
+        entry.delta = delta;
+        entry.prev = prev;
+        entry.next = cursor;
     }
 
-    pub fn poll(&mut self) -> Option<T> {
-        let &(delta, _) = self.0.front()?;
+    /// Takes the entry of `id` out of the list, keeping its handle.
+    fn unlink(&mut self, id: TimerId) {
+        let entry = self.entry_mut(id);
+        let delta = entry.delta;
+        let prev = entry.prev.take();
+        let next = entry.next.take();
 
-        if delta == 0 {
-            self.0.pop_front().map(|(_, event)| event)
+        if let Some(next) = next {
+            let entry = self.entry_mut(next);
+            entry.delta = entry.delta.saturating_add(delta);
+            entry.prev = prev;
+        }
+        match prev {
+            Some(prev) => self.entry_mut(prev).next = next,
+            None => self.head = next,
+        }
+    }
+
+    /// Removes a timer for good, whether it is running or paused.
+    fn take(&mut self, id: TimerId) -> Option<Entry<T, D>> {
+        let entry = self.entries.get(id)?;
+        Some(if entry.paused {
+            self.release(id)
         } else {
-            None
+            self.release_linked(id)
+        })
+    }
+
+    fn release_linked(&mut self, id: TimerId) -> Entry<T, D> {
+        self.unlink(id);
+        self.release(id)
+    }
+
+    /// Frees the handle of an entry that is no longer in the list.
+    fn release(&mut self, id: TimerId) -> Entry<T, D> {
+        let entry = self.entries.remove(id).expect("released timers exist");
+        if entry.paused {
+            self.paused.remove(&id);
+        }
+
+        if let Some(group) = entry.group {
+            // Draining a group takes its members out of the index first.
+            if let Some(members) = self.groups.get_mut(&group) {
+                members.remove(&id);
+                if members.is_empty() {
+                    self.groups.remove(&group);
+                }
+            }
+        }
+        entry
+    }
+}
+
+impl<T, D: TimeUnit> Default for PullTimer<T, D> {
+    fn default() -> Self {
+        PullTimer {
+            entries: Slots::new(),
+            head: None,
+            paused: BTreeSet::new(),
+            groups: BTreeMap::new(),
+            horizon: D::MAX,
+            now: D::ZERO,
         }
     }
 }
@@ -87,6 +780,8 @@ impl<T> PullTimer<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
+    use core::time::Duration;
 
     #[test]
     fn timer_preserves_fifo_order() {
@@ -136,6 +831,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn timer_update_spans_several_deadlines() {
+        let mut timer = PullTimer::new();
+
+        timer.add(3, "a");
+        timer.add(5, "b");
+
+        timer.update(4);
+        assert_eq!(timer.poll(), Some("a"));
+        assert_eq!(timer.next_in(), Some(1));
+    }
+
     #[test]
     fn timer_next_in() {
         let mut timer = PullTimer::new();
@@ -187,4 +894,504 @@ mod tests {
             timer.update(1);
         }
     }
+
+    #[test]
+    fn timer_conformance() {
+        crate::conformance::run(PullTimer::new);
+    }
+
+    #[test]
+    fn timer_counts_in_other_units() {
+        let mut millis = PullTimer::<_, u64>::default();
+        millis.add(u64::from(u32::MAX) * 2, "later");
+        millis.update(u64::from(u32::MAX));
+        assert_eq!(millis.next_in(), Some(u64::from(u32::MAX)));
+
+        let mut timer = PullTimer::default();
+        timer.add(Duration::from_millis(1500), "slow");
+        timer.add(Duration::from_millis(500), "fast");
+
+        timer.update(Duration::from_secs(1));
+        assert_eq!(timer.poll(), Some("fast"));
+        assert_eq!(timer.next_in(), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn timer_handles_deadlines_near_max() {
+        let mut timer = PullTimer::new();
+
+        timer.add(u32::MAX, "last");
+        timer.add(u32::MAX - 1, "second");
+        timer.add(1, "first");
+
+        timer.update(u32::MAX - 1);
+        assert_eq!(timer.poll(), Some("first"));
+        assert_eq!(timer.poll(), Some("second"));
+        assert_eq!(timer.remove("last"), Some(1));
+    }
+
+    #[test]
+    fn timer_try_add_beyond_horizon() {
+        let mut timer = PullTimer::new();
+        timer.set_horizon(1000);
+
+        assert!(timer.try_add(1000, "edge").is_ok());
+        let error = timer.try_add(1001, "beyond").unwrap_err();
+        assert_eq!(error.into_inner(), "beyond");
+        assert_eq!(timer.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "horizon")]
+    fn timer_add_beyond_horizon_panics() {
+        let mut timer = PullTimer::new();
+        timer.set_horizon(10);
+        timer.add(11, ());
+    }
+
+    #[test]
+    fn timer_advance_reports_lateness() {
+        let mut timer = PullTimer::new();
+
+        timer.add(3, "a");
+        timer.add(5, "b");
+        timer.add(5, "c");
+        timer.add(20, "d");
+
+        let fired: Vec<_> = timer
+            .advance(4)
+            .map(|expired| (expired.event, expired.lateness))
+            .collect();
+        assert_eq!(fired, [("a", 1)]);
+
+        timer.update(2);
+        timer.update(3);
+        let fired: Vec<_> = timer
+            .advance(0)
+            .map(|expired| (expired.event, expired.lateness))
+            .collect();
+        assert_eq!(fired, [("b", 4), ("c", 4)]);
+        assert_eq!(timer.next_in(), Some(11));
+    }
+
+    #[test]
+    fn interval_repeats_without_drift() {
+        let mut timer = PullTimer::new();
+        let id = timer.add_interval(10, "tick");
+
+        timer.update(13);
+        assert_eq!(timer.poll(), Some("tick"));
+        assert_eq!(timer.next_in(), Some(7));
+
+        timer.update(7);
+        assert_eq!(timer.poll(), Some("tick"));
+        assert_eq!(timer.remaining(id), Some(10));
+
+        assert_eq!(timer.cancel(id), Some("tick"));
+        assert!(timer.is_empty());
+    }
+
+    #[test]
+    fn interval_stops_after_count() {
+        let mut timer = PullTimer::new();
+        let id = timer.add_interval_with(Interval::new(5).delay(0).times(3), ());
+
+        let mut fired = 0;
+        for _ in 0..30 {
+            while timer.poll().is_some() {
+                fired += 1;
+            }
+            timer.update(1);
+        }
+
+        assert_eq!(fired, 3);
+        assert!(!timer.contains(id));
+    }
+
+    #[test]
+    fn interval_missed_ticks() {
+        let fire = |missed| {
+            let mut timer = PullTimer::new();
+            let id = timer.add_interval_with(Interval::new(10).missed(missed), ());
+            timer.update(35);
+
+            let fired: Vec<_> = timer.advance(0).map(|expired| expired.lateness).collect();
+            (fired, timer.remaining(id))
+        };
+
+        assert_eq!(fire(MissedTicks::Burst), (vec![25, 15, 5], Some(5)));
+        assert_eq!(fire(MissedTicks::Skip), (vec![25], Some(5)));
+        assert_eq!(fire(MissedTicks::Delay), (vec![25], Some(10)));
+    }
+
+    #[test]
+    fn timer_reschedule() {
+        let mut timer = PullTimer::new();
+
+        let a = timer.add(10, "a");
+        timer.add(20, "b");
+        timer.add(20, "c");
+        timer.update(5);
+
+        assert_eq!(timer.reschedule(a, 15), Some(5));
+        assert_eq!(timer.remaining(a), Some(15));
+        assert_eq!(timer.postpone(a, 10), Some(15));
+        assert_eq!(timer.expedite(a, 100), Some(25));
+        assert_eq!(timer.remaining(a), Some(0));
+
+        let fired: Vec<_> = timer.advance(15).map(|expired| expired.event).collect();
+        assert_eq!(fired, ["a", "b", "c"]);
+        assert_eq!(timer.reschedule(a, 1), None);
+    }
+
+    #[test]
+    fn timer_reschedule_keeps_order_of_others() {
+        let mut timer = PullTimer::new();
+
+        timer.add(5, 1);
+        let moved = timer.add(5, 2);
+        timer.add(5, 3);
+        timer.add(9, 4);
+
+        timer.postpone(moved, 3);
+
+        let fired: Vec<_> = timer.advance(10).map(|expired| expired.event).collect();
+        assert_eq!(fired, [1, 3, 2, 4]);
+    }
+
+    #[test]
+    fn timer_iter_clones_without_cloning_events() {
+        struct Event(u32);
+
+        let mut timer = PullTimer::new();
+        timer.add(10, Event(1));
+        timer.add(20, Event(2));
+
+        let mut iter = timer.iter();
+        assert_eq!(iter.next().map(|(_, event)| event.0), Some(1));
+
+        let rest = iter.clone();
+        assert_eq!(iter.map(|(_, event)| event.0).collect::<Vec<_>>(), [2]);
+        assert_eq!(
+            rest.map(|(remaining, _)| remaining).collect::<Vec<_>>(),
+            [20]
+        );
+    }
+
+    #[test]
+    fn timer_iterates_in_firing_order() {
+        let mut timer = PullTimer::new();
+
+        timer.add(30, "c");
+        timer.add(10, "a");
+        timer.add(20, "b");
+        timer.update(5);
+
+        assert_eq!(timer.peek(), Some((&"a", 5)));
+        assert_eq!(
+            timer.iter().collect::<Vec<_>>(),
+            [(5, &"a"), (15, &"b"), (25, &"c")]
+        );
+
+        for (remaining, event) in &mut timer {
+            if remaining > 10 {
+                *event = "late";
+            }
+        }
+
+        assert_eq!(
+            timer.into_iter().collect::<Vec<_>>(),
+            [(5, "a"), (15, "late"), (25, "late")]
+        );
+    }
+
+    #[test]
+    fn timer_retain() {
+        let mut timer = PullTimer::new();
+
+        for (deadline, client) in [(10, 1), (20, 2), (30, 1), (40, 3)] {
+            timer.add(deadline, client);
+        }
+
+        timer.retain(|_, &client| client != 1);
+        assert_eq!(timer.len(), 2);
+        assert_eq!(timer.iter().collect::<Vec<_>>(), [(20, &2), (40, &3)]);
+    }
+
+    #[test]
+    fn timer_extract_if() {
+        let mut timer = PullTimer::new();
+
+        for (deadline, client) in [(10, 1), (20, 2), (30, 1), (40, 3)] {
+            timer.add(deadline, client);
+        }
+        timer.update(5);
+
+        let extracted = timer.extract_if(|remaining, &client| client == 1 || remaining > 30);
+        assert_eq!(extracted, [(5, 1), (25, 1), (35, 3)]);
+
+        assert_eq!(timer.next_in(), Some(15));
+        timer.update(15);
+        assert_eq!(timer.poll(), Some(2));
+        assert!(timer.is_empty());
+    }
+
+    #[test]
+    fn timer_groups() {
+        let level = GroupId(1);
+        let menu = GroupId(2);
+        let mut timer = PullTimer::new();
+
+        timer.add_to_group(level, 30, "spawn");
+        timer.add(10, "autosave");
+        let id = timer.add_to_group(level, 20, "door");
+        timer.add_to_group(menu, 5, "blink");
+
+        assert_eq!(timer.len_group(level), 2);
+        assert_eq!(timer.next_in_group(level), Some(20));
+        assert_eq!(timer.next_in_group(GroupId(3)), None);
+
+        timer.cancel(id);
+        assert_eq!(timer.len_group(level), 1);
+        assert_eq!(timer.drain_group(level).len(), 1);
+        assert_eq!(timer.len_group(level), 0);
+
+        assert_eq!(timer.cancel_group(menu), 1);
+        assert_eq!(timer.cancel_group(menu), 0);
+        assert_eq!(timer.iter().collect::<Vec<_>>(), [(10, &"autosave")]);
+    }
+
+    #[test]
+    fn timer_group_operations_leave_other_timers_alone() {
+        let group = GroupId(1);
+        let mut timer = PullTimer::new();
+
+        timer.add(5, "a");
+        let spawn = timer.add_to_group(group, 10, "spawn");
+        timer.add(15, "b");
+        let tick = timer.add_interval_with(Interval::new(4).group(group), "tick");
+        timer.add(25, "c");
+        timer.set_horizon(30);
+
+        assert!(timer.try_add_to_group(group, 31, "late").is_err());
+        assert_eq!(timer.len_group(group), 2);
+        assert_eq!(timer.next_in_group(group), Some(4));
+
+        let mut drained = timer.drain_group(group);
+        drained.sort();
+        assert_eq!(drained, [(spawn, "spawn"), (tick, "tick")]);
+        assert_eq!(
+            timer.iter().collect::<Vec<_>>(),
+            [(5, &"a"), (15, &"b"), (25, &"c")]
+        );
+    }
+
+    #[test]
+    fn timer_group_count_follows_polls() {
+        let group = GroupId(7);
+        let mut timer = PullTimer::new();
+
+        timer.add_to_group(group, 0, ());
+        timer.add_to_group(group, 4, ());
+        timer.poll();
+        assert_eq!(timer.len_group(group), 1);
+
+        timer.retain(|_, _| false);
+        assert_eq!(timer.len_group(group), 0);
+    }
+
+    #[test]
+    fn timer_pause_and_resume() {
+        let mut timer = PullTimer::new();
+
+        let stunned = timer.add(10, "stunned");
+        timer.add(15, "other");
+        timer.update(4);
+
+        assert!(timer.pause(stunned));
+        assert!(!timer.pause(stunned));
+        assert!(timer.is_paused(stunned));
+        assert_eq!(timer.remaining(stunned), Some(6));
+        assert_eq!(timer.next_in(), Some(11));
+        assert_eq!(timer.len(), 2);
+
+        timer.update(20);
+        assert_eq!(timer.poll(), Some("other"));
+        assert_eq!(timer.poll(), None);
+
+        assert!(timer.resume(stunned));
+        assert!(!timer.resume(stunned));
+        assert!(!timer.is_paused(stunned));
+        assert_eq!(timer.next_in(), Some(6));
+
+        timer.update(6);
+        assert_eq!(timer.poll(), Some("stunned"));
+        assert!(timer.is_empty());
+    }
+
+    #[test]
+    fn timer_paused_timers_stay_in_group() {
+        let group = GroupId(1);
+        let mut timer = PullTimer::new();
+
+        let id = timer.add_to_group(group, 10, "paused");
+        let running = timer.add_to_group(group, 20, "running");
+        timer.pause(id);
+
+        assert_eq!(timer.next_in_group(group), Some(20));
+        assert_eq!(timer.len_group(group), 2);
+        let mut drained = timer.drain_group(group);
+        drained.sort();
+        assert_eq!(drained, [(id, "paused"), (running, "running")]);
+        assert!(!timer.contains(id));
+        assert!(timer.is_empty());
+    }
+
+    #[test]
+    fn timer_cancel_by_id() {
+        let mut timer = PullTimer::new();
+
+        let first = timer.add(10, "same");
+        let second = timer.add(20, "same");
+
+        assert!(timer.contains(first));
+        assert_eq!(timer.remaining(second), Some(20));
+        assert_eq!(timer.cancel(first), Some("same"));
+        assert!(!timer.contains(first));
+        assert_eq!(timer.cancel(first), None);
+        assert_eq!(timer.remaining(second), Some(20));
+
+        timer.update(20);
+        assert_eq!(timer.poll(), Some("same"));
+        assert!(!timer.contains(second));
+    }
+
+    #[test]
+    fn timer_stale_id_does_not_match_reused_slot() {
+        let mut timer = PullTimer::new();
+
+        let stale = timer.add(0, 1);
+        assert_eq!(timer.poll(), Some(1));
+
+        let fresh = timer.add(5, 2);
+        assert_ne!(stale, fresh);
+        assert!(!timer.contains(stale));
+        assert_eq!(timer.remaining(stale), None);
+        assert_eq!(timer.cancel(stale), None);
+        assert_eq!(timer.remaining(fresh), Some(5));
+    }
+
+    #[test]
+    fn timer_tracks_absolute_time() {
+        let mut timer = PullTimer::new();
+
+        timer.update(1000);
+        timer.update(500);
+        assert_eq!(timer.now(), 1500);
+
+        let id = timer.add_at(5000, "at");
+        assert_eq!(timer.remaining(id), Some(3500));
+        assert_eq!(timer.deadline_of(id), Some(5000));
+
+        timer.advance_to(4999);
+        assert_eq!(timer.poll(), None);
+        timer.advance_to(4000);
+        assert_eq!(timer.now(), 4999);
+
+        timer.advance_to(5002);
+        assert_eq!(timer.deadline_of(id), Some(5000));
+        let expired = timer.poll_expired().unwrap();
+        assert_eq!((expired.event, expired.lateness), ("at", 2));
+    }
+
+    #[test]
+    fn timer_add_at_in_the_past_is_due_and_late() {
+        let mut timer = PullTimer::new();
+
+        timer.update(100);
+        timer.add_at(90, "recent");
+        let id = timer.add_at(20, "old");
+        timer.add_at(100, "now");
+
+        assert_eq!(timer.next_in(), Some(0));
+        assert_eq!(timer.deadline_of(id), Some(20));
+        let fired: Vec<_> = timer.advance(0).map(|e| (e.event, e.lateness)).collect();
+        assert_eq!(fired, [("old", 80), ("recent", 10), ("now", 0)]);
+    }
+
+    #[test]
+    fn timer_advance_to_next_skips_idle_time() {
+        let mut timer = PullTimer::new();
+
+        timer.add(30, "b");
+        timer.add(10, "a");
+        timer.add(30, "c");
+
+        assert_eq!(timer.advance_to_next(), Some((10, "a")));
+        assert_eq!(timer.advance_to_next(), Some((20, "b")));
+        assert_eq!(timer.advance_to_next(), Some((0, "c")));
+        assert_eq!(timer.advance_to_next(), None);
+        assert_eq!(timer.now(), 30);
+    }
+
+    #[test]
+    fn timer_run_until_handles_scheduled_events() {
+        let mut timer = PullTimer::new();
+        let mut log = Vec::new();
+
+        timer.add(5, 0);
+        let handled = timer.run_until(100, |timer, generation| {
+            log.push((timer.now(), generation));
+            if generation < 10 {
+                timer.add(20, generation + 1);
+            }
+        });
+
+        assert_eq!(handled, 5);
+        assert_eq!(log, [(5, 0), (25, 1), (45, 2), (65, 3), (85, 4)]);
+        assert_eq!(timer.now(), 100);
+        assert_eq!(timer.next_in(), Some(5));
+    }
+
+    #[test]
+    fn timer_bulk_operations_include_paused_timers() {
+        let mut timer = PullTimer::new();
+
+        let paused = timer.add(10, 1);
+        timer.add(20, 2);
+        timer.add(30, 1);
+        timer.pause(paused);
+
+        assert_eq!(
+            timer.iter().collect::<Vec<_>>(),
+            [(20, &2), (30, &1), (10, &1)]
+        );
+        for (_, client) in timer.iter_mut() {
+            *client *= 10;
+        }
+        assert_eq!(timer.get(paused), Some(&10));
+
+        let mut retained = timer.clone();
+        retained.retain(|_, &client| client != 10);
+        assert_eq!(retained.len(), 1);
+        assert_eq!(retained.iter().collect::<Vec<_>>(), [(20, &20)]);
+        assert!(!retained.contains(paused));
+
+        let mut extracted = timer.clone();
+        assert_eq!(
+            extracted.extract_if(|_, &client| client == 10),
+            [(30, 10), (10, 10)]
+        );
+        assert_eq!(extracted.len(), 1);
+
+        let mut removed = timer.clone();
+        assert_eq!(removed.remove(10), Some(30));
+        assert_eq!(removed.remove(10), Some(10));
+        assert!(!removed.contains(paused));
+        assert_eq!(removed.len(), 1);
+
+        let drained = timer.into_iter();
+        assert_eq!(drained.len(), 3);
+        assert_eq!(drained.collect::<Vec<_>>(), [(20, 20), (30, 10), (10, 10)]);
+    }
 }
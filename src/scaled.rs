@@ -0,0 +1,290 @@
+use crate::{PullTimer, TimerId};
+
+/// A [`PullTimer`] whose time runs at a rational multiple of real time, for
+/// slow motion, fast forward and pausing.
+///
+/// Deadlines and remaining times are in the timer's own time, while
+/// [`update`](ScaledTimer::update) and [`next_in`](ScaledTimer::next_in)
+/// are in real time. Fractions of a tick left over by the scaling are
+/// carried between updates, so no time is lost.
+#[derive(Debug, Clone)]
+pub struct ScaledTimer<T> {
+    timer: PullTimer<T>,
+    numerator: u32,
+    denominator: u32,
+    /// The fraction of a tick not yet passed on to the timer, in units of
+    /// `1 / unit` ticks. `unit` is a multiple of `denominator`, larger only
+    /// while a fraction left over from an earlier speed needs it.
+    carry: u64,
+    unit: u64,
+    paused: bool,
+}
+
+impl<T> ScaledTimer<T> {
+    pub fn new() -> ScaledTimer<T> {
+        ScaledTimer {
+            timer: PullTimer::new(),
+            numerator: 1,
+            denominator: 1,
+            carry: 0,
+            unit: 1,
+            paused: false,
+        }
+    }
+
+    pub fn timer(&self) -> &PullTimer<T> {
+        &self.timer
+    }
+
+    pub fn timer_mut(&mut self) -> &mut PullTimer<T> {
+        &mut self.timer
+    }
+
+    /// Returns the speed as a `(numerator, denominator)` pair.
+    pub fn speed(&self) -> (u32, u32) {
+        (self.numerator, self.denominator)
+    }
+
+    /// Makes the timer run `numerator / denominator` times as fast as real
+    /// time. A speed of zero stops it, like [`pause`](ScaledTimer::pause).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is zero.
+    pub fn set_speed(&mut self, numerator: u32, denominator: u32) {
+        assert!(denominator != 0, "denominator must not be zero");
+
+        // Keep the carried fraction exactly, in a unit both speeds divide.
+        let new_denominator = u64::from(denominator);
+        match (self.unit / gcd(self.unit, new_denominator)).checked_mul(new_denominator) {
+            Some(unit) => {
+                self.carry *= unit / self.unit;
+                self.unit = unit;
+            }
+            None => {
+                // Too fine to represent, so round down to the new speed.
+                let carry = u128::from(self.carry) * u128::from(new_denominator);
+                self.carry = (carry / u128::from(self.unit)) as u64;
+                self.unit = new_denominator;
+            }
+        }
+
+        self.numerator = numerator;
+        self.denominator = denominator;
+        self.reduce();
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns whether the timer's time is currently moving.
+    pub fn is_running(&self) -> bool {
+        !self.paused && self.numerator != 0
+    }
+
+    /// Returns the real time until the next event fires, or `None` if there
+    /// is none or the timer is stopped.
+    pub fn next_in(&self) -> Option<u32> {
+        self.real_time(self.timer.next_in()?)
+    }
+
+    /// Advances the timer by `elapsed` real time.
+    pub fn update(&mut self, elapsed: u32) {
+        let local = self.scale(u64::from(elapsed));
+        self.advance_local(local);
+    }
+
+    pub fn add(&mut self, deadline: u32, event: T) -> TimerId {
+        self.timer.add(deadline, event)
+    }
+
+    pub fn cancel(&mut self, id: TimerId) -> Option<T> {
+        self.timer.cancel(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.timer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timer.is_empty()
+    }
+
+    pub fn poll(&mut self) -> Option<T> {
+        self.timer.poll()
+    }
+
+    /// Converts `elapsed` real time into the timer's own time, keeping the
+    /// fractional part in `carry`.
+    pub(crate) fn scale(&mut self, elapsed: u64) -> u64 {
+        if !self.is_running() {
+            return 0;
+        }
+
+        let scaled = u128::from(elapsed) * self.rate() + u128::from(self.carry);
+        let unit = u128::from(self.unit);
+        self.carry = (scaled % unit) as u64;
+        self.reduce();
+        u64::try_from(scaled / unit).unwrap_or(u64::MAX)
+    }
+
+    pub(crate) fn advance_local(&mut self, local: u64) {
+        let first = u32::try_from(local).unwrap_or(u32::MAX);
+        self.timer.update(first);
+
+        // After a full first step every pending event is due, and a second
+        // full step saturates the clock and all lateness, so the rest of
+        // `local` would change nothing.
+        let rest = u32::try_from(local - u64::from(first)).unwrap_or(u32::MAX);
+        if rest > 0 {
+            self.timer.update(rest);
+        }
+    }
+
+    /// Returns the real time needed for `local` of the timer's own time to
+    /// pass, or `None` if it never will.
+    pub(crate) fn real_time(&self, local: u32) -> Option<u32> {
+        if local == 0 {
+            return Some(0);
+        }
+        if !self.is_running() {
+            return None;
+        }
+
+        let needed = u128::from(local) * u128::from(self.unit) - u128::from(self.carry);
+        let real = needed.div_ceil(self.rate());
+        Some(u32::try_from(real).unwrap_or(u32::MAX))
+    }
+
+    /// Returns how many `1 / unit` ticks pass per tick of real time.
+    fn rate(&self) -> u128 {
+        u128::from(self.numerator) * u128::from(self.unit / u64::from(self.denominator))
+    }
+
+    /// Shrinks `unit` back towards `denominator` as far as `carry` allows.
+    fn reduce(&mut self) {
+        let common = gcd(self.carry, self.unit / u64::from(self.denominator));
+        self.carry /= common;
+        self.unit /= common;
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl<T> Default for ScaledTimer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaled_timer_slow_motion_carries_fractions() {
+        let mut timer = ScaledTimer::new();
+        timer.set_speed(1, 3);
+        timer.add(2, "hit");
+
+        assert_eq!(timer.next_in(), Some(6));
+
+        for _ in 0..5 {
+            timer.update(1);
+            assert_eq!(timer.poll(), None);
+        }
+        assert_eq!(timer.next_in(), Some(1));
+
+        timer.update(1);
+        assert_eq!(timer.poll(), Some("hit"));
+    }
+
+    #[test]
+    fn scaled_timer_fast_forward() {
+        let mut timer = ScaledTimer::new();
+        timer.set_speed(5, 2);
+        timer.add(10, "done");
+
+        assert_eq!(timer.next_in(), Some(4));
+        timer.update(3);
+        assert_eq!(timer.poll(), None);
+        assert_eq!(timer.next_in(), Some(1));
+        timer.update(1);
+        assert_eq!(timer.poll(), Some("done"));
+    }
+
+    #[test]
+    fn scaled_timer_at_full_speed_updates_at_once() {
+        let mut timer = ScaledTimer::new();
+        timer.set_speed(u32::MAX, 1);
+        timer.add(u32::MAX, "late");
+        timer.add(1, "early");
+
+        timer.update(u32::MAX);
+        assert_eq!(timer.poll(), Some("early"));
+        assert_eq!(timer.poll(), Some("late"));
+        assert_eq!(timer.timer().now(), u32::MAX);
+    }
+
+    #[test]
+    fn scaled_timer_pause() {
+        let mut timer = ScaledTimer::new();
+        timer.add(10, "tick");
+
+        timer.pause();
+        timer.update(100);
+        assert_eq!(timer.poll(), None);
+        assert_eq!(timer.next_in(), None);
+
+        timer.resume();
+        timer.set_speed(0, 1);
+        assert!(!timer.is_running());
+        assert_eq!(timer.next_in(), None);
+
+        timer.set_speed(2, 1);
+        assert_eq!(timer.next_in(), Some(5));
+        timer.update(5);
+        assert_eq!(timer.poll(), Some("tick"));
+    }
+
+    #[test]
+    fn scaled_timer_keeps_uneven_fraction_across_speed_changes() {
+        let mut timer = ScaledTimer::new();
+        timer.add(1, "hit");
+
+        // 1/3 + 1/2 + 1/3 of a tick adds up to more than one.
+        timer.set_speed(1, 3);
+        timer.update(1);
+        timer.set_speed(1, 2);
+        timer.update(1);
+        timer.set_speed(1, 3);
+        assert_eq!(timer.next_in(), Some(1));
+        timer.update(1);
+        assert_eq!(timer.poll(), Some("hit"));
+    }
+
+    #[test]
+    fn scaled_timer_keeps_fraction_across_speed_changes() {
+        let mut timer = ScaledTimer::new();
+        timer.set_speed(1, 2);
+        timer.add(2, ());
+
+        timer.update(1);
+        timer.set_speed(1, 4);
+        assert_eq!(timer.next_in(), Some(6));
+    }
+}
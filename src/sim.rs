@@ -0,0 +1,451 @@
+//! A small discrete-event simulation kernel.
+//!
+//! A [`Simulation`] owns the model state and an event calendar kept in a
+//! [`PullTimer`]. Handlers get the whole simulation, so they can change the
+//! state and schedule further events; the clock jumps straight from one
+//! event to the next. [`Resource`], [`Tally`] and [`TimeWeighted`] cover
+//! the usual queues and statistics.
+
+use crate::{PullTimer, TimerId};
+use alloc::collections::{BTreeMap, VecDeque};
+
+/// An event that can be told apart from others of a different kind, so the
+/// simulation can count each kind separately.
+pub trait Event {
+    fn kind(&self) -> &'static str;
+}
+
+/// Model state of type `S`, driven by events of type `E` in simulated time.
+#[derive(Debug, Clone)]
+pub struct Simulation<S, E> {
+    state: S,
+    calendar: PullTimer<E, u64>,
+    counts: BTreeMap<&'static str, u64>,
+}
+
+impl<S, E: Event> Simulation<S, E> {
+    pub fn new(state: S) -> Simulation<S, E> {
+        Simulation {
+            state,
+            calendar: PullTimer::default(),
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the current simulated time.
+    pub fn now(&self) -> u64 {
+        self.calendar.now()
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    pub fn into_state(self) -> S {
+        self.state
+    }
+
+    /// Schedules `event` to happen `delay` after the current time.
+    pub fn schedule(&mut self, delay: u64, event: E) -> TimerId {
+        self.calendar.add(delay, event)
+    }
+
+    /// Schedules `event` at the absolute time `at`, which happens at once if
+    /// it has already passed.
+    pub fn schedule_at(&mut self, at: u64, event: E) -> TimerId {
+        self.calendar.add_at(at, event)
+    }
+
+    pub fn cancel(&mut self, id: TimerId) -> Option<E> {
+        self.calendar.cancel(id)
+    }
+
+    /// Returns the number of events still to happen.
+    pub fn pending(&self) -> usize {
+        self.calendar.len()
+    }
+
+    /// Returns how many events of `kind` have been handled.
+    pub fn count(&self, kind: &str) -> u64 {
+        self.counts.get(kind).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of events handled of each kind, by kind.
+    pub fn counts(&self) -> impl Iterator<Item = (&'static str, u64)> + '_ {
+        self.counts.iter().map(|(&kind, &count)| (kind, count))
+    }
+
+    /// Handles the next event, moving the clock to it. Returns `false` if no
+    /// events are left.
+    pub fn step<H>(&mut self, mut handler: H) -> bool
+    where
+        H: FnMut(&mut Self, E),
+    {
+        match self.calendar.advance_to_next() {
+            Some((_, event)) => {
+                self.dispatch(event, &mut handler);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Handles events until none are left.
+    pub fn run<H>(&mut self, mut handler: H)
+    where
+        H: FnMut(&mut Self, E),
+    {
+        while self.step(&mut handler) {}
+    }
+
+    /// Handles every event up to the absolute time `limit`, then moves the
+    /// clock to `limit`.
+    pub fn run_until<H>(&mut self, limit: u64, mut handler: H)
+    where
+        H: FnMut(&mut Self, E),
+    {
+        PullTimer::run_until_in(
+            self,
+            |sim| &mut sim.calendar,
+            limit,
+            |sim, event| sim.dispatch(event, &mut handler),
+        );
+    }
+
+    fn dispatch<H>(&mut self, event: E, handler: &mut H)
+    where
+        H: FnMut(&mut Self, E),
+    {
+        *self.counts.entry(event.kind()).or_insert(0) += 1;
+        handler(self, event);
+    }
+}
+
+/// A resource with a fixed number of units, such as the servers of a queue.
+/// Requests that cannot be served at once wait in line in FIFO order.
+#[derive(Debug, Clone)]
+pub struct Resource<T> {
+    capacity: usize,
+    busy: usize,
+    waiting: VecDeque<T>,
+}
+
+impl<T> Resource<T> {
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Resource<T> {
+        assert!(capacity != 0, "capacity must not be zero");
+
+        Resource {
+            capacity,
+            busy: 0,
+            waiting: VecDeque::new(),
+        }
+    }
+
+    /// Seizes a unit for `request` and hands it back if one is free.
+    /// Otherwise the request waits in line and `None` is returned.
+    pub fn request(&mut self, request: T) -> Option<T> {
+        if self.busy < self.capacity {
+            self.busy += 1;
+            Some(request)
+        } else {
+            self.waiting.push_back(request);
+            None
+        }
+    }
+
+    /// Frees a unit. If a request is waiting, the unit goes straight to it
+    /// and the request is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no unit is busy.
+    pub fn release(&mut self) -> Option<T> {
+        assert!(self.busy != 0, "no unit of the resource is busy");
+
+        let next = self.waiting.pop_front();
+        if next.is_none() {
+            self.busy -= 1;
+        }
+        next
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn busy(&self) -> usize {
+        self.busy
+    }
+
+    pub fn waiting(&self) -> usize {
+        self.waiting.len()
+    }
+}
+
+/// Summary statistics of a series of observations, such as waiting times.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tally {
+    count: u64,
+    mean: f64,
+    squares: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Tally {
+    pub fn new() -> Tally {
+        Tally::default()
+    }
+
+    pub fn record(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+
+        // Welford's method, which stays accurate over long runs.
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squares += delta * (value - self.mean);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count != 0).then_some(self.mean)
+    }
+
+    /// Returns the sample variance, which needs at least two observations.
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 1).then(|| self.squares / (self.count - 1) as f64)
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        (self.count != 0).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        (self.count != 0).then_some(self.max)
+    }
+}
+
+/// The average over time of a level that changes at discrete moments, such
+/// as the length of a queue.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeWeighted {
+    start: u64,
+    last: u64,
+    level: f64,
+    area: f64,
+}
+
+impl TimeWeighted {
+    /// Starts tracking `level` from the time `now`.
+    pub fn new(now: u64, level: f64) -> TimeWeighted {
+        TimeWeighted {
+            start: now,
+            last: now,
+            level,
+            area: 0.0,
+        }
+    }
+
+    pub fn level(&self) -> f64 {
+        self.level
+    }
+
+    /// Records that the level changed to `level` at the time `now`.
+    pub fn set(&mut self, now: u64, level: f64) {
+        self.area += self.level * now.saturating_sub(self.last) as f64;
+        self.last = self.last.max(now);
+        self.level = level;
+    }
+
+    /// Returns the average level from the start up to `now`.
+    pub fn mean(&self, now: u64) -> Option<f64> {
+        let span = now.saturating_sub(self.start);
+        let area = self.area + self.level * now.saturating_sub(self.last) as f64;
+        (span != 0).then(|| area / span as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUSTOMERS: u64 = 200_000;
+    const MEAN_ARRIVAL: f64 = 1000.0;
+    const MEAN_SERVICE: f64 = 800.0;
+
+    #[derive(Debug)]
+    enum Bank {
+        Arrival,
+        Departure,
+    }
+
+    impl Event for Bank {
+        fn kind(&self) -> &'static str {
+            match self {
+                Bank::Arrival => "arrival",
+                Bank::Departure => "departure",
+            }
+        }
+    }
+
+    struct Model {
+        random: u64,
+        arrived: u64,
+        teller: Resource<u64>,
+        wait: Tally,
+        in_system: TimeWeighted,
+    }
+
+    impl Model {
+        /// Samples an exponential distribution by inverting its CDF.
+        fn exponential(&mut self, mean: f64) -> u64 {
+            self.random ^= self.random << 13;
+            self.random ^= self.random >> 7;
+            self.random ^= self.random << 17;
+
+            let uniform = ((self.random >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+            (-mean * uniform.ln()) as u64
+        }
+    }
+
+    fn bank(sim: &mut Simulation<Model, Bank>, event: Bank) {
+        let now = sim.now();
+        let model = sim.state_mut();
+
+        match event {
+            Bank::Arrival => {
+                model.arrived += 1;
+                let level = model.in_system.level();
+                model.in_system.set(now, level + 1.0);
+
+                let next = model.exponential(MEAN_ARRIVAL);
+                let more = model.arrived < CUSTOMERS;
+                let served = model.teller.request(now).is_some();
+                let service = model.exponential(MEAN_SERVICE);
+                if served {
+                    model.wait.record(0.0);
+                }
+
+                if more {
+                    sim.schedule(next, Bank::Arrival);
+                }
+                if served {
+                    sim.schedule(service, Bank::Departure);
+                }
+            }
+            Bank::Departure => {
+                let level = model.in_system.level();
+                model.in_system.set(now, level - 1.0);
+
+                if let Some(arrived) = model.teller.release() {
+                    model.wait.record((now - arrived) as f64);
+                    let service = model.exponential(MEAN_SERVICE);
+                    sim.schedule(service, Bank::Departure);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn simulation_mm1_queue_matches_theory() {
+        let mut sim = Simulation::new(Model {
+            random: 0x2545_f491_4f6c_dd1d,
+            arrived: 0,
+            teller: Resource::new(1),
+            wait: Tally::new(),
+            in_system: TimeWeighted::new(0, 0.0),
+        });
+
+        sim.schedule(0, Bank::Arrival);
+        sim.run(bank);
+
+        assert_eq!(sim.count("arrival"), CUSTOMERS);
+        assert_eq!(sim.count("departure"), CUSTOMERS);
+        assert_eq!(sim.pending(), 0);
+
+        // For utilization 0.8, a customer waits 0.8 / 0.2 service times in
+        // line on average, and 4 customers are in the system.
+        let utilization = MEAN_SERVICE / MEAN_ARRIVAL;
+        let wait = utilization / (1.0 - utilization) * MEAN_SERVICE;
+        let in_system = utilization / (1.0 - utilization);
+
+        let model = sim.state();
+        let measured_wait = model.wait.mean().unwrap();
+        let measured_in_system = model.in_system.mean(sim.now()).unwrap();
+        assert!((measured_wait / wait - 1.0).abs() < 0.1, "{measured_wait}");
+        assert!(
+            (measured_in_system / in_system - 1.0).abs() < 0.1,
+            "{measured_in_system}"
+        );
+    }
+
+    #[test]
+    fn simulation_run_until_stops_at_limit() {
+        struct Tick;
+
+        impl Event for Tick {
+            fn kind(&self) -> &'static str {
+                "tick"
+            }
+        }
+
+        let mut sim = Simulation::new(0);
+        sim.schedule(10, Tick);
+        sim.run_until(35, |sim, tick| {
+            *sim.state_mut() += 1;
+            sim.schedule(10, tick);
+        });
+
+        assert_eq!(*sim.state(), 3);
+        assert_eq!(sim.now(), 35);
+        assert_eq!(sim.counts().collect::<alloc::vec::Vec<_>>(), [("tick", 3)]);
+    }
+
+    #[test]
+    fn resource_serves_in_fifo_order() {
+        let mut resource = Resource::new(2);
+
+        assert_eq!(resource.request('a'), Some('a'));
+        assert_eq!(resource.request('b'), Some('b'));
+        assert_eq!(resource.request('c'), None);
+        assert_eq!(resource.request('d'), None);
+        assert_eq!(resource.waiting(), 2);
+
+        assert_eq!(resource.release(), Some('c'));
+        assert_eq!(resource.release(), Some('d'));
+        assert_eq!(resource.release(), None);
+        assert_eq!(resource.busy(), 1);
+    }
+
+    #[test]
+    fn tally_summarizes_observations() {
+        let mut tally = Tally::new();
+        assert_eq!(tally.mean(), None);
+
+        for value in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            tally.record(value);
+        }
+
+        assert_eq!(tally.count(), 8);
+        assert_eq!(tally.mean(), Some(5.0));
+        assert_eq!(tally.variance(), Some(32.0 / 7.0));
+        assert_eq!((tally.min(), tally.max()), (Some(2.0), Some(9.0)));
+    }
+}
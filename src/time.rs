@@ -0,0 +1,67 @@
+use core::time::Duration;
+
+/// A representation of time that timers can count in.
+///
+/// Implemented for the unsigned integers from `u16` to `u128`, which count
+/// in whatever unit the caller chooses, and for [`Duration`].
+pub trait TimeUnit: Copy + Ord {
+    const ZERO: Self;
+
+    const MAX: Self;
+
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    fn saturating_sub(self, rhs: Self) -> Self;
+
+    /// Returns the remainder of dividing by a non-zero `rhs`.
+    fn rem(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_time_unit {
+    ($($ty:ty),*) => {
+        $(
+            impl TimeUnit for $ty {
+                const ZERO: Self = 0;
+
+                const MAX: Self = <$ty>::MAX;
+
+                fn saturating_add(self, rhs: Self) -> Self {
+                    <$ty>::saturating_add(self, rhs)
+                }
+
+                fn saturating_sub(self, rhs: Self) -> Self {
+                    <$ty>::saturating_sub(self, rhs)
+                }
+
+                fn rem(self, rhs: Self) -> Self {
+                    self % rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_time_unit!(u16, u32, u64, u128);
+
+impl TimeUnit for Duration {
+    const ZERO: Self = Duration::ZERO;
+
+    const MAX: Self = Duration::MAX;
+
+    fn saturating_add(self, rhs: Self) -> Self {
+        Duration::saturating_add(self, rhs)
+    }
+
+    fn saturating_sub(self, rhs: Self) -> Self {
+        Duration::saturating_sub(self, rhs)
+    }
+
+    fn rem(self, rhs: Self) -> Self {
+        let nanos = self.as_nanos() % rhs.as_nanos();
+        // The remainder is smaller than `rhs`, so it fits back into a `Duration`.
+        Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        )
+    }
+}
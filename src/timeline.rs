@@ -0,0 +1,304 @@
+use crate::{ScaledTimer, TimerId};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+/// A handle to a timeline in a [`Timelines`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimelineId(u32);
+
+impl TimelineId {
+    /// The root timeline, which runs in real time unless slowed down or
+    /// paused itself.
+    pub const ROOT: TimelineId = TimelineId(0);
+}
+
+#[derive(Debug, Clone)]
+struct Timeline<T> {
+    timer: ScaledTimer<T>,
+    parent: Option<TimelineId>,
+}
+
+/// A tree of [`ScaledTimer`]s, each advancing by its parent's local time
+/// at its own speed.
+///
+/// Pausing a timeline also stops all timelines below it. For example, a
+/// world clock and a UI clock can both hang off the root, so the world can
+/// be paused while the UI keeps running.
+#[derive(Debug, Clone)]
+pub struct Timelines<T> {
+    timelines: Vec<Timeline<T>>,
+}
+
+impl<T> Timelines<T> {
+    pub fn new() -> Timelines<T> {
+        Timelines {
+            timelines: vec![Timeline {
+                timer: ScaledTimer::new(),
+                parent: None,
+            }],
+        }
+    }
+
+    /// Adds a timeline below `parent` running at `numerator / denominator`
+    /// times its parent's speed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parent` is not a timeline of this tree or `denominator`
+    /// is zero.
+    pub fn add_timeline(
+        &mut self,
+        parent: TimelineId,
+        numerator: u32,
+        denominator: u32,
+    ) -> TimelineId {
+        assert!(
+            self.get(parent).is_some(),
+            "parent is not a timeline of this tree"
+        );
+
+        let mut timer = ScaledTimer::new();
+        timer.set_speed(numerator, denominator);
+
+        let id = TimelineId(self.timelines.len() as u32);
+        self.timelines.push(Timeline {
+            timer,
+            parent: Some(parent),
+        });
+        id
+    }
+
+    pub fn get(&self, timeline: TimelineId) -> Option<&ScaledTimer<T>> {
+        self.timelines
+            .get(timeline.0 as usize)
+            .map(|timeline| &timeline.timer)
+    }
+
+    pub fn get_mut(&mut self, timeline: TimelineId) -> Option<&mut ScaledTimer<T>> {
+        self.timelines
+            .get_mut(timeline.0 as usize)
+            .map(|timeline| &mut timeline.timer)
+    }
+
+    pub fn parent(&self, timeline: TimelineId) -> Option<TimelineId> {
+        self.timelines.get(timeline.0 as usize)?.parent
+    }
+
+    /// Returns the real time until the next event of any running timeline
+    /// fires.
+    pub fn next_in(&self) -> Option<u32> {
+        (0..self.timelines.len())
+            .filter_map(|index| {
+                let local = self.timelines[index].timer.timer().next_in()?;
+                self.real_time(TimelineId(index as u32), local)
+            })
+            .min()
+    }
+
+    /// Advances the root timeline by `elapsed` real time, and every other
+    /// timeline by its parent's local time.
+    pub fn update(&mut self, elapsed: u32) {
+        let mut passed = vec![0; self.timelines.len()];
+
+        // Parents always come before their children.
+        for index in 0..self.timelines.len() {
+            let outer = match self.timelines[index].parent {
+                Some(parent) => passed[parent.0 as usize],
+                None => u64::from(elapsed),
+            };
+            let timer = &mut self.timelines[index].timer;
+            passed[index] = timer.scale(outer);
+            timer.advance_local(passed[index]);
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `timeline` is not a timeline of this tree.
+    pub fn add(&mut self, timeline: TimelineId, deadline: u32, event: T) -> TimerId {
+        self.get_mut(timeline)
+            .expect("not a timeline of this tree")
+            .add(deadline, event)
+    }
+
+    pub fn cancel(&mut self, timeline: TimelineId, id: TimerId) -> Option<T> {
+        self.get_mut(timeline)?.cancel(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.timelines
+            .iter()
+            .map(|timeline| timeline.timer.len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a due event together with the timeline it was added to.
+    ///
+    /// Events from different timelines come out in the order they fell due
+    /// in real time, judged by how late each is at the timelines' current
+    /// speeds. Events due at the same time come from the timeline that was
+    /// created first.
+    pub fn poll(&mut self) -> Option<(TimelineId, T)> {
+        let (_, Reverse(index)) = (0..self.timelines.len())
+            .filter_map(|index| {
+                let lateness = self.timelines[index].timer.timer().overdue_by()?;
+                Some((self.real_lateness(index, lateness), Reverse(index)))
+            })
+            .max()?;
+
+        let event = self.timelines[index].timer.poll()?;
+        Some((TimelineId(index as u32), event))
+    }
+
+    /// Converts how late an event on the timeline at `index` is into real
+    /// time. Timelines that are stopped had their events fall due before
+    /// they stopped, so those count as the latest.
+    fn real_lateness(&self, mut index: usize, lateness: u32) -> u128 {
+        let mut lateness = u128::from(lateness);
+        loop {
+            let timeline = &self.timelines[index];
+            if !timeline.timer.is_running() {
+                return u128::MAX;
+            }
+            let (numerator, denominator) = timeline.timer.speed();
+            lateness = lateness.saturating_mul(u128::from(denominator)) / u128::from(numerator);
+
+            match timeline.parent {
+                Some(parent) => index = parent.0 as usize,
+                None => return lateness,
+            }
+        }
+    }
+
+    /// Converts `local` time on `timeline` into real time by walking up to
+    /// the root, or `None` if a timeline on the way is stopped.
+    fn real_time(&self, mut timeline: TimelineId, mut local: u32) -> Option<u32> {
+        loop {
+            let current = &self.timelines[timeline.0 as usize];
+            local = current.timer.real_time(local)?;
+
+            match current.parent {
+                Some(parent) => timeline = parent,
+                None => return Some(local),
+            }
+        }
+    }
+}
+
+impl<T> Default for Timelines<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timelines_run_at_their_own_speed() {
+        let mut timelines = Timelines::new();
+        let world = timelines.add_timeline(TimelineId::ROOT, 1, 1);
+        let slow = timelines.add_timeline(world, 1, 2);
+
+        timelines.add(TimelineId::ROOT, 5, "root");
+        timelines.add(slow, 3, "slow");
+        assert_eq!(timelines.next_in(), Some(5));
+
+        timelines.update(5);
+        assert_eq!(timelines.poll(), Some((TimelineId::ROOT, "root")));
+        assert_eq!(timelines.poll(), None);
+        assert_eq!(timelines.next_in(), Some(1));
+
+        timelines.update(1);
+        assert_eq!(timelines.poll(), Some((slow, "slow")));
+        assert!(timelines.is_empty());
+    }
+
+    #[test]
+    fn timelines_poll_in_firing_order() {
+        let mut timelines = Timelines::new();
+        let child = timelines.add_timeline(TimelineId::ROOT, 1, 1);
+        let slow = timelines.add_timeline(TimelineId::ROOT, 1, 2);
+
+        timelines.add(TimelineId::ROOT, 5, "root");
+        timelines.add(child, 1, "child");
+        timelines.add(slow, 1, "slow");
+        timelines.add(child, 4, "child later");
+
+        timelines.update(5);
+        assert_eq!(timelines.poll(), Some((child, "child")));
+        assert_eq!(timelines.poll(), Some((slow, "slow")));
+        assert_eq!(timelines.poll(), Some((child, "child later")));
+        assert_eq!(timelines.poll(), Some((TimelineId::ROOT, "root")));
+        assert_eq!(timelines.poll(), None);
+    }
+
+    #[test]
+    fn pausing_a_timeline_stops_its_children() {
+        let mut timelines = Timelines::new();
+        let ui = timelines.add_timeline(TimelineId::ROOT, 1, 1);
+        let world = timelines.add_timeline(TimelineId::ROOT, 1, 1);
+        let entity = timelines.add_timeline(world, 2, 1);
+
+        timelines.add(ui, 10, "menu");
+        timelines.add(entity, 10, "attack");
+        assert_eq!(timelines.next_in(), Some(5));
+
+        timelines.get_mut(world).unwrap().pause();
+        assert_eq!(timelines.next_in(), Some(10));
+
+        timelines.update(10);
+        assert_eq!(timelines.poll(), Some((ui, "menu")));
+        assert_eq!(timelines.poll(), None);
+        assert_eq!(timelines.next_in(), None);
+
+        timelines.get_mut(world).unwrap().resume();
+        assert_eq!(timelines.next_in(), Some(5));
+        timelines.update(5);
+        assert_eq!(timelines.poll(), Some((entity, "attack")));
+    }
+
+    #[test]
+    fn timelines_poll_paused_timelines_first() {
+        for stop in [ScaledTimer::pause, |timer: &mut ScaledTimer<_>| {
+            timer.set_speed(0, 1)
+        }] {
+            let mut timelines = Timelines::new();
+            let ui = timelines.add_timeline(TimelineId::ROOT, 1, 1);
+            let world = timelines.add_timeline(TimelineId::ROOT, 1, 1);
+
+            timelines.add(world, 5, "world");
+            timelines.add(ui, 10, "ui");
+            timelines.update(5);
+            stop(timelines.get_mut(world).unwrap());
+            timelines.update(5);
+
+            assert_eq!(timelines.poll(), Some((world, "world")));
+            assert_eq!(timelines.poll(), Some((ui, "ui")));
+            assert_eq!(timelines.poll(), None);
+        }
+    }
+
+    #[test]
+    fn timelines_carry_fractions_through_the_chain() {
+        let mut timelines = Timelines::new();
+        let half = timelines.add_timeline(TimelineId::ROOT, 1, 2);
+        let third = timelines.add_timeline(half, 1, 3);
+
+        timelines.add(third, 1, ());
+        assert_eq!(timelines.next_in(), Some(6));
+
+        for _ in 0..5 {
+            timelines.update(1);
+            assert_eq!(timelines.poll(), None);
+        }
+        timelines.update(1);
+        assert_eq!(timelines.poll(), Some((third, ())));
+    }
+}
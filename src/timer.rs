@@ -0,0 +1,98 @@
+use crate::{PullTimer, TimeUnit};
+
+/// The operations shared by every timer in this crate, so that code can be
+/// written once against any of them, or against a mock.
+///
+/// See [`conformance`](crate::conformance) for checking that an
+/// implementation behaves like [`PullTimer`](crate::PullTimer).
+pub trait Timer<T> {
+    type Time: TimeUnit;
+
+    fn add(&mut self, deadline: Self::Time, event: T);
+
+    fn update(&mut self, elapsed: Self::Time);
+
+    fn poll(&mut self) -> Option<T>;
+
+    fn next_in(&self) -> Option<Self::Time>;
+
+    fn remove(&mut self, event: T) -> Option<Self::Time>
+    where
+        T: PartialEq;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+macro_rules! impl_timer {
+    ($($timer:ident),*) => {
+        $(
+            impl<T> Timer<T> for crate::$timer<T> {
+                type Time = u32;
+
+                fn add(&mut self, deadline: u32, event: T) {
+                    crate::$timer::add(self, deadline, event);
+                }
+
+                fn update(&mut self, elapsed: u32) {
+                    crate::$timer::update(self, elapsed)
+                }
+
+                fn poll(&mut self) -> Option<T> {
+                    crate::$timer::poll(self)
+                }
+
+                fn next_in(&self) -> Option<u32> {
+                    crate::$timer::next_in(self)
+                }
+
+                fn remove(&mut self, event: T) -> Option<u32>
+                where
+                    T: PartialEq,
+                {
+                    crate::$timer::remove(self, event)
+                }
+
+                fn len(&self) -> usize {
+                    crate::$timer::len(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_timer!(TreeTimer, WheelTimer);
+
+impl<T, D: TimeUnit> Timer<T> for PullTimer<T, D> {
+    type Time = D;
+
+    fn add(&mut self, deadline: D, event: T) {
+        PullTimer::add(self, deadline, event);
+    }
+
+    fn update(&mut self, elapsed: D) {
+        PullTimer::update(self, elapsed)
+    }
+
+    fn poll(&mut self) -> Option<T> {
+        PullTimer::poll(self)
+    }
+
+    fn next_in(&self) -> Option<D> {
+        PullTimer::next_in(self)
+    }
+
+    fn remove(&mut self, event: T) -> Option<D>
+    where
+        T: PartialEq,
+    {
+        PullTimer::remove(self, event)
+    }
+
+    fn len(&self) -> usize {
+        PullTimer::len(self)
+    }
+}
@@ -0,0 +1,163 @@
+use crate::TimerId;
+use crate::id::Slots;
+use alloc::collections::BTreeMap;
+
+type Key = (u64, u64);
+
+/// A timer with the same semantics as [`PullTimer`](crate::PullTimer), kept
+/// in an ordered tree of absolute deadlines so that adding and cancelling
+/// take `O(log n)` instead of `O(n)`.
+#[derive(Debug, Clone)]
+pub struct TreeTimer<T> {
+    tree: BTreeMap<Key, (TimerId, T)>,
+    slots: Slots<Key>,
+    now: u64,
+    sequence: u64,
+}
+
+impl<T> TreeTimer<T> {
+    pub fn new() -> TreeTimer<T> {
+        TreeTimer {
+            tree: BTreeMap::new(),
+            slots: Slots::new(),
+            now: 0,
+            sequence: 0,
+        }
+    }
+
+    pub fn next_in(&self) -> Option<u32> {
+        let (&(deadline, _), _) = self.tree.first_key_value()?;
+        Some(self.remaining_from(deadline))
+    }
+
+    pub fn update(&mut self, elapsed: u32) {
+        self.now += u64::from(elapsed);
+    }
+
+    pub fn add(&mut self, deadline: u32, event: T) -> TimerId {
+        let key = (self.now + u64::from(deadline), self.sequence);
+        self.sequence += 1;
+
+        let id = self.slots.insert(key);
+        self.tree.insert(key, (id, event));
+        id
+    }
+
+    pub fn remove(&mut self, event: T) -> Option<u32>
+    where
+        T: PartialEq,
+    {
+        let (&key, &(id, _)) = self
+            .tree
+            .iter()
+            .find(|(_, (_, element))| *element == event)?;
+
+        self.tree.remove(&key);
+        self.slots.remove(id);
+        Some(self.remaining_from(key.0))
+    }
+
+    pub fn cancel(&mut self, id: TimerId) -> Option<T> {
+        let key = self.slots.remove(id)?;
+        self.tree.remove(&key).map(|(_, event)| event)
+    }
+
+    pub fn contains(&self, id: TimerId) -> bool {
+        self.slots.contains(id)
+    }
+
+    pub fn remaining(&self, id: TimerId) -> Option<u32> {
+        let &(deadline, _) = self.slots.get(id)?;
+        Some(self.remaining_from(deadline))
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn poll(&mut self) -> Option<T> {
+        let entry = self.tree.first_entry()?;
+
+        if entry.key().0 <= self.now {
+            let (id, event) = entry.remove();
+            self.slots.remove(id);
+            Some(event)
+        } else {
+            None
+        }
+    }
+
+    fn remaining_from(&self, deadline: u64) -> u32 {
+        // Deadlines are at most `u32::MAX` past the time they were added at.
+        deadline.saturating_sub(self.now) as u32
+    }
+}
+
+impl<T> Default for TreeTimer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree_timer_conformance() {
+        crate::conformance::run(TreeTimer::new);
+    }
+
+    #[test]
+    fn tree_timer_preserves_fifo_order() {
+        let mut timer = TreeTimer::new();
+
+        timer.add(5, "first");
+        timer.update(10);
+        timer.add(0, "second");
+        timer.add(0, "third");
+
+        assert_eq!(timer.poll(), Some("first"));
+        assert_eq!(timer.poll(), Some("second"));
+        assert_eq!(timer.poll(), Some("third"));
+        assert_eq!(timer.poll(), None);
+    }
+
+    #[test]
+    fn tree_timer_fires_in_time() {
+        let mut timer = TreeTimer::new();
+
+        timer.add(40, 40);
+        timer.add(20, 20);
+        timer.add(0, 0);
+        timer.add(30, 30);
+        timer.add(10, 10);
+
+        for i in 0..=41 {
+            if let Some(value) = timer.poll() {
+                assert_eq!(value, i);
+            }
+            timer.update(1);
+        }
+    }
+
+    #[test]
+    fn tree_timer_remove_and_cancel() {
+        let mut timer = TreeTimer::new();
+
+        timer.add(100, "boom!");
+        let id = timer.add(30, "tick");
+        timer.update(20);
+
+        assert_eq!(timer.next_in(), Some(10));
+        assert_eq!(timer.remaining(id), Some(10));
+        assert_eq!(timer.cancel(id), Some("tick"));
+        assert_eq!(timer.cancel(id), None);
+        assert_eq!(timer.remove("boom!"), Some(80));
+        assert_eq!(timer.next_in(), None);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::TimerId;
+use crate::id::Slots;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone)]
+struct WheelEntry<T> {
+    deadline: u64,
+    sequence: u64,
+    id: TimerId,
+    event: T,
+}
+
+#[derive(Debug, Clone)]
+struct Level<T> {
+    occupied: u64,
+    slots: Vec<Vec<WheelEntry<T>>>,
+}
+
+/// A hierarchical timing wheel with the same semantics as
+/// [`PullTimer`](crate::PullTimer).
+///
+/// Each level has `slots` buckets, and every bucket of a level spans a whole
+/// turn of the level below it. Entries are moved down a level whenever time
+/// reaches their bucket, and deadlines beyond the last level wait in an
+/// overflow list until they come into range.
+#[derive(Debug, Clone)]
+pub struct WheelTimer<T> {
+    levels: Vec<Level<T>>,
+    overflow: Vec<WheelEntry<T>>,
+    ready: VecDeque<WheelEntry<T>>,
+    slots: Slots<u64>,
+    bits: u32,
+    now: u64,
+    sequence: u64,
+}
+
+impl<T> WheelTimer<T> {
+    pub fn new() -> WheelTimer<T> {
+        WheelTimer::with_config(64, 6)
+    }
+
+    /// Creates a wheel with `levels` levels of `slots` buckets each.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slots` is not a power of two between 2 and 64, if `levels`
+    /// is zero, or if the wheel would span more than `2^63` ticks.
+    pub fn with_config(slots: usize, levels: usize) -> WheelTimer<T> {
+        assert!(
+            slots.is_power_of_two() && (2..=64).contains(&slots),
+            "slot count must be a power of two between 2 and 64"
+        );
+        assert!(levels > 0, "a wheel needs at least one level");
+
+        let bits = slots.trailing_zeros();
+        assert!(
+            (bits as usize) * levels < 64,
+            "a wheel may span at most 2^63 ticks"
+        );
+
+        WheelTimer {
+            levels: (0..levels)
+                .map(|_| Level {
+                    occupied: 0,
+                    slots: (0..slots).map(|_| Vec::new()).collect(),
+                })
+                .collect(),
+            overflow: Vec::new(),
+            ready: VecDeque::new(),
+            slots: Slots::new(),
+            bits,
+            now: 0,
+            sequence: 0,
+        }
+    }
+
+    pub fn next_in(&self) -> Option<u32> {
+        if !self.ready.is_empty() {
+            return Some(0);
+        }
+
+        let deadline = match self.next_bucket() {
+            Some((time, Some((0, _)))) => time,
+            Some((_, Some((level, slot)))) => self.levels[level].slots[slot]
+                .iter()
+                .map(|entry| entry.deadline)
+                .min()?,
+            Some((_, None)) => self.overflow.iter().map(|entry| entry.deadline).min()?,
+            None => return None,
+        };
+
+        Some(self.remaining_from(deadline))
+    }
+
+    pub fn update(&mut self, elapsed: u32) {
+        let target = self.now + u64::from(elapsed);
+
+        while let Some((time, bucket)) = self.next_bucket() {
+            if time > target {
+                break;
+            }
+            self.now = time;
+
+            let entries = match bucket {
+                Some((level, slot)) => {
+                    let level = &mut self.levels[level];
+                    level.occupied &= !(1 << slot);
+                    core::mem::take(&mut level.slots[slot])
+                }
+                None => core::mem::take(&mut self.overflow),
+            };
+
+            let mut due = Vec::new();
+            for entry in entries {
+                if entry.deadline == self.now {
+                    due.push(entry);
+                } else {
+                    self.place(entry);
+                }
+            }
+            due.sort_by_key(|entry| entry.sequence);
+            self.ready.extend(due);
+        }
+
+        self.now = target;
+    }
+
+    pub fn add(&mut self, deadline: u32, event: T) -> TimerId {
+        let deadline = self.now + u64::from(deadline);
+        let id = self.slots.insert(deadline);
+
+        let entry = WheelEntry {
+            deadline,
+            sequence: self.sequence,
+            id,
+            event,
+        };
+        self.sequence += 1;
+
+        if deadline == self.now {
+            self.ready.push_back(entry);
+        } else {
+            self.place(entry);
+        }
+        id
+    }
+
+    pub fn remove(&mut self, event: T) -> Option<u32>
+    where
+        T: PartialEq,
+    {
+        let id = match self.ready.iter().find(|entry| entry.event == event) {
+            Some(entry) => entry.id,
+            None => {
+                self.levels
+                    .iter()
+                    .flat_map(|level| level.slots.iter().flatten())
+                    .chain(&self.overflow)
+                    .filter(|entry| entry.event == event)
+                    .min_by_key(|entry| (entry.deadline, entry.sequence))?
+                    .id
+            }
+        };
+
+        let remaining = self.remaining(id);
+        self.cancel(id);
+        remaining
+    }
+
+    pub fn cancel(&mut self, id: TimerId) -> Option<T> {
+        let deadline = self.slots.remove(id)?;
+
+        let entry = if deadline <= self.now {
+            let index = self.ready.iter().position(|entry| entry.id == id)?;
+            self.ready.remove(index)?
+        } else {
+            match self.bucket_of(deadline) {
+                Some((level, slot)) => {
+                    let level = &mut self.levels[level];
+                    let bucket = &mut level.slots[slot];
+                    let index = bucket.iter().position(|entry| entry.id == id)?;
+                    let entry = bucket.remove(index);
+                    if bucket.is_empty() {
+                        level.occupied &= !(1 << slot);
+                    }
+                    entry
+                }
+                None => {
+                    let index = self.overflow.iter().position(|entry| entry.id == id)?;
+                    self.overflow.remove(index)
+                }
+            }
+        };
+
+        Some(entry.event)
+    }
+
+    pub fn contains(&self, id: TimerId) -> bool {
+        self.slots.contains(id)
+    }
+
+    pub fn remaining(&self, id: TimerId) -> Option<u32> {
+        let &deadline = self.slots.get(id)?;
+        Some(self.remaining_from(deadline))
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn poll(&mut self) -> Option<T> {
+        let entry = self.ready.pop_front()?;
+        self.slots.remove(entry.id);
+        Some(entry.event)
+    }
+
+    fn remaining_from(&self, deadline: u64) -> u32 {
+        // Deadlines are at most `u32::MAX` past the time they were added at.
+        deadline.saturating_sub(self.now) as u32
+    }
+
+    /// Returns the level and slot a future deadline belongs in, or `None` if
+    /// it is beyond the last level.
+    fn bucket_of(&self, deadline: u64) -> Option<(usize, usize)> {
+        let highest_bit = 63 - (deadline ^ self.now).leading_zeros();
+        let level = (highest_bit / self.bits) as usize;
+
+        if level < self.levels.len() {
+            let slot = (deadline >> (self.bits * level as u32)) as usize & (self.slot_count() - 1);
+            Some((level, slot))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the start time of the earliest non-empty bucket, along with
+    /// its level and slot, or `None` for the overflow list.
+    fn next_bucket(&self) -> Option<(u64, Option<(usize, usize)>)> {
+        for (index, level) in self.levels.iter().enumerate() {
+            let shift = self.bits * index as u32;
+            let current = (self.now >> shift) as usize & (self.slot_count() - 1);
+            let pending = level.occupied & (u64::MAX << current);
+
+            if pending != 0 {
+                let slot = pending.trailing_zeros() as usize;
+                let span = shift + self.bits;
+                let time = (self.now >> span << span) | ((slot as u64) << shift);
+                return Some((time, Some((index, slot))));
+            }
+        }
+
+        let span = self.bits * self.levels.len() as u32;
+        let time = self
+            .overflow
+            .iter()
+            .map(|entry| entry.deadline >> span << span)
+            .min()?;
+        Some((time, None))
+    }
+
+    fn place(&mut self, entry: WheelEntry<T>) {
+        match self.bucket_of(entry.deadline) {
+            Some((level, slot)) => {
+                let level = &mut self.levels[level];
+                level.occupied |= 1 << slot;
+                level.slots[slot].push(entry);
+            }
+            None => self.overflow.push(entry),
+        }
+    }
+
+    fn slot_count(&self) -> usize {
+        1 << self.bits
+    }
+}
+
+impl<T> Default for WheelTimer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wheel_timer_conformance() {
+        crate::conformance::run(WheelTimer::new);
+        crate::conformance::run(|| WheelTimer::with_config(4, 2));
+    }
+
+    #[test]
+    fn wheel_timer_fires_in_order_within_a_tick() {
+        let mut timer = WheelTimer::with_config(2, 1);
+
+        timer.add(9, "c");
+        timer.add(7, "a");
+        timer.add(7, "b");
+        timer.add(9, "d");
+
+        timer.update(8);
+        assert_eq!(timer.poll(), Some("a"));
+        assert_eq!(timer.poll(), Some("b"));
+        assert_eq!(timer.next_in(), Some(1));
+
+        timer.update(1);
+        assert_eq!(timer.poll(), Some("c"));
+        assert_eq!(timer.poll(), Some("d"));
+        assert_eq!(timer.poll(), None);
+    }
+
+    #[test]
+    fn wheel_timer_cancel() {
+        let mut timer = WheelTimer::new();
+
+        let near = timer.add(3, "near");
+        let far = timer.add(100_000, "far");
+
+        assert_eq!(timer.remaining(far), Some(100_000));
+        assert_eq!(timer.cancel(near), Some("near"));
+        assert_eq!(timer.cancel(near), None);
+        assert_eq!(timer.next_in(), Some(100_000));
+
+        timer.update(100_000);
+        assert_eq!(timer.poll(), Some("far"));
+        assert!(!timer.contains(far));
+    }
+}